@@ -0,0 +1,444 @@
+//! Read the results of ODBC catalog functions (e.g. `SQLTables` or `SQLColumns`) as Arrow record
+//! batches.
+//!
+//! The result sets of catalog functions are specified by the ODBC standard, yet drivers are free to
+//! append additional driver specific columns. The readers created by the functions in this module
+//! always use the schemas returned by [`tables_schema`], [`columns_schema`],
+//! [`primary_keys_schema`], [`foreign_keys_schema`] and [`statistics_schema`] respectively. Driver
+//! specific columns are not fetched. Every field in these schemas is nullable, since drivers are not
+//! always consistent in reporting values for columns which are mandated by the standard to be non
+//! `NULL`.
+//!
+//! `SQLPrimaryKeys` and `SQLStatistics` are not (yet) exposed by `odbc-api`, so we call them
+//! directly.
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use odbc_api::{
+    handles::{SqlChar, SqlText, Statement, StatementImpl},
+    sys::{HStmt, SmallInt, SqlReturn, USmallInt},
+    Connection, CursorImpl,
+};
+
+use crate::{numeric::into_result, Error, OdbcReader, OdbcReaderBuilder};
+
+/// Schema of the record batches emitted by readers created with [`tables_as_arrow`].
+///
+/// | Name          | Type |
+/// | ------------- | ---- |
+/// | `TABLE_CAT`   | Utf8 |
+/// | `TABLE_SCHEM` | Utf8 |
+/// | `TABLE_NAME`  | Utf8 |
+/// | `TABLE_TYPE`  | Utf8 |
+/// | `REMARKS`     | Utf8 |
+pub fn tables_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("TABLE_CAT", DataType::Utf8, true),
+        Field::new("TABLE_SCHEM", DataType::Utf8, true),
+        Field::new("TABLE_NAME", DataType::Utf8, true),
+        Field::new("TABLE_TYPE", DataType::Utf8, true),
+        Field::new("REMARKS", DataType::Utf8, true),
+    ])
+}
+
+/// Schema of the record batches emitted by readers created with [`columns_as_arrow`].
+///
+/// | Name                | Type  |
+/// | ------------------- | ----- |
+/// | `TABLE_CAT`         | Utf8  |
+/// | `TABLE_SCHEM`       | Utf8  |
+/// | `TABLE_NAME`        | Utf8  |
+/// | `COLUMN_NAME`       | Utf8  |
+/// | `DATA_TYPE`         | Int16 |
+/// | `TYPE_NAME`         | Utf8  |
+/// | `COLUMN_SIZE`       | Int32 |
+/// | `BUFFER_LENGTH`     | Int32 |
+/// | `DECIMAL_DIGITS`    | Int16 |
+/// | `NUM_PREC_RADIX`    | Int16 |
+/// | `NULLABLE`          | Int16 |
+/// | `REMARKS`           | Utf8  |
+/// | `COLUMN_DEF`        | Utf8  |
+/// | `SQL_DATA_TYPE`     | Int16 |
+/// | `SQL_DATETIME_SUB`  | Int16 |
+/// | `CHAR_OCTET_LENGTH` | Int32 |
+/// | `ORDINAL_POSITION`  | Int32 |
+/// | `IS_NULLABLE`       | Utf8  |
+pub fn columns_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("TABLE_CAT", DataType::Utf8, true),
+        Field::new("TABLE_SCHEM", DataType::Utf8, true),
+        Field::new("TABLE_NAME", DataType::Utf8, true),
+        Field::new("COLUMN_NAME", DataType::Utf8, true),
+        Field::new("DATA_TYPE", DataType::Int16, true),
+        Field::new("TYPE_NAME", DataType::Utf8, true),
+        Field::new("COLUMN_SIZE", DataType::Int32, true),
+        Field::new("BUFFER_LENGTH", DataType::Int32, true),
+        Field::new("DECIMAL_DIGITS", DataType::Int16, true),
+        Field::new("NUM_PREC_RADIX", DataType::Int16, true),
+        Field::new("NULLABLE", DataType::Int16, true),
+        Field::new("REMARKS", DataType::Utf8, true),
+        Field::new("COLUMN_DEF", DataType::Utf8, true),
+        Field::new("SQL_DATA_TYPE", DataType::Int16, true),
+        Field::new("SQL_DATETIME_SUB", DataType::Int16, true),
+        Field::new("CHAR_OCTET_LENGTH", DataType::Int32, true),
+        Field::new("ORDINAL_POSITION", DataType::Int32, true),
+        Field::new("IS_NULLABLE", DataType::Utf8, true),
+    ])
+}
+
+/// Schema of the record batches emitted by readers created with [`primary_keys_as_arrow`].
+///
+/// | Name          | Type  |
+/// | ------------- | ----- |
+/// | `TABLE_CAT`   | Utf8  |
+/// | `TABLE_SCHEM` | Utf8  |
+/// | `TABLE_NAME`  | Utf8  |
+/// | `COLUMN_NAME` | Utf8  |
+/// | `KEY_SEQ`     | Int16 |
+/// | `PK_NAME`     | Utf8  |
+pub fn primary_keys_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("TABLE_CAT", DataType::Utf8, true),
+        Field::new("TABLE_SCHEM", DataType::Utf8, true),
+        Field::new("TABLE_NAME", DataType::Utf8, true),
+        Field::new("COLUMN_NAME", DataType::Utf8, true),
+        Field::new("KEY_SEQ", DataType::Int16, true),
+        Field::new("PK_NAME", DataType::Utf8, true),
+    ])
+}
+
+/// Schema of the record batches emitted by readers created with [`foreign_keys_as_arrow`].
+///
+/// | Name            | Type  |
+/// | --------------- | ----- |
+/// | `PKTABLE_CAT`   | Utf8  |
+/// | `PKTABLE_SCHEM` | Utf8  |
+/// | `PKTABLE_NAME`  | Utf8  |
+/// | `PKCOLUMN_NAME` | Utf8  |
+/// | `FKTABLE_CAT`   | Utf8  |
+/// | `FKTABLE_SCHEM` | Utf8  |
+/// | `FKTABLE_NAME`  | Utf8  |
+/// | `FKCOLUMN_NAME` | Utf8  |
+/// | `KEY_SEQ`       | Int16 |
+/// | `UPDATE_RULE`   | Int16 |
+/// | `DELETE_RULE`   | Int16 |
+/// | `FK_NAME`       | Utf8  |
+/// | `PK_NAME`       | Utf8  |
+/// | `DEFERRABILITY` | Int16 |
+pub fn foreign_keys_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("PKTABLE_CAT", DataType::Utf8, true),
+        Field::new("PKTABLE_SCHEM", DataType::Utf8, true),
+        Field::new("PKTABLE_NAME", DataType::Utf8, true),
+        Field::new("PKCOLUMN_NAME", DataType::Utf8, true),
+        Field::new("FKTABLE_CAT", DataType::Utf8, true),
+        Field::new("FKTABLE_SCHEM", DataType::Utf8, true),
+        Field::new("FKTABLE_NAME", DataType::Utf8, true),
+        Field::new("FKCOLUMN_NAME", DataType::Utf8, true),
+        Field::new("KEY_SEQ", DataType::Int16, true),
+        Field::new("UPDATE_RULE", DataType::Int16, true),
+        Field::new("DELETE_RULE", DataType::Int16, true),
+        Field::new("FK_NAME", DataType::Utf8, true),
+        Field::new("PK_NAME", DataType::Utf8, true),
+        Field::new("DEFERRABILITY", DataType::Int16, true),
+    ])
+}
+
+/// Schema of the record batches emitted by readers created with [`statistics_as_arrow`].
+///
+/// | Name               | Type  |
+/// | ------------------ | ----- |
+/// | `TABLE_CAT`        | Utf8  |
+/// | `TABLE_SCHEM`      | Utf8  |
+/// | `TABLE_NAME`       | Utf8  |
+/// | `NON_UNIQUE`       | Int16 |
+/// | `INDEX_QUALIFIER`  | Utf8  |
+/// | `INDEX_NAME`       | Utf8  |
+/// | `TYPE`             | Int16 |
+/// | `ORDINAL_POSITION` | Int16 |
+/// | `COLUMN_NAME`      | Utf8  |
+/// | `ASC_OR_DESC`      | Utf8  |
+/// | `CARDINALITY`      | Int32 |
+/// | `PAGES`            | Int32 |
+/// | `FILTER_CONDITION` | Utf8  |
+pub fn statistics_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("TABLE_CAT", DataType::Utf8, true),
+        Field::new("TABLE_SCHEM", DataType::Utf8, true),
+        Field::new("TABLE_NAME", DataType::Utf8, true),
+        Field::new("NON_UNIQUE", DataType::Int16, true),
+        Field::new("INDEX_QUALIFIER", DataType::Utf8, true),
+        Field::new("INDEX_NAME", DataType::Utf8, true),
+        Field::new("TYPE", DataType::Int16, true),
+        Field::new("ORDINAL_POSITION", DataType::Int16, true),
+        Field::new("COLUMN_NAME", DataType::Utf8, true),
+        Field::new("ASC_OR_DESC", DataType::Utf8, true),
+        Field::new("CARDINALITY", DataType::Int32, true),
+        Field::new("PAGES", DataType::Int32, true),
+        Field::new("FILTER_CONDITION", DataType::Utf8, true),
+    ])
+}
+
+/// List tables, schemas, views and catalogs of a data source as Arrow record batches. The batches
+/// are described by [`tables_schema`].
+///
+/// # Parameters
+///
+/// * `connection`: Connection used to execute `SQLTables`.
+/// * `catalog_name`: Filter result by catalog name. Accept search patterns. Use `%` to match any
+///   number of characters. Use `_` to match exactly on character. Use `\` to escape characeters.
+/// * `schema_name`: Filter result by schema. Accepts patterns in the same way as `catalog_name`.
+/// * `table_name`: Filter result by table. Accepts patterns in the same way as `catalog_name`.
+/// * `table_type`: Filters results by table type. E.g: 'TABLE', 'VIEW'. This argument accepts a
+///   comma separeted list of table types. Pass an empty string to not filter the result by table
+///   type at all.
+/// * `reader_builder`: Used to configure buffer sizes of the reader. Any schema set on the builder
+///   is ignored in favour of [`tables_schema`].
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{odbc_api::Connection, tables_as_arrow, OdbcReaderBuilder};
+///
+/// fn print_table_names(connection: &Connection<'_>) -> Result<(), anyhow::Error> {
+///     let reader = tables_as_arrow(connection, "", "", "%", "TABLE", &OdbcReaderBuilder::new())?;
+///     for batch in reader {
+///         let batch = batch?;
+///         println!("{:?}", batch.column_by_name("TABLE_NAME"));
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn tables_as_arrow<'c>(
+    connection: &'c Connection<'_>,
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    table_type: &str,
+    reader_builder: &OdbcReaderBuilder,
+) -> Result<OdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+    let cursor = connection
+        .tables(catalog_name, schema_name, table_name, table_type)
+        .map_err(|source| Error::CatalogFunction {
+            function: "SQLTables",
+            source,
+        })?;
+    build_with_schema(reader_builder, tables_schema(), cursor)
+}
+
+/// Describe the columns of all tables matching the patterns as Arrow record batches. The batches
+/// are described by [`columns_schema`].
+///
+/// # Parameters
+///
+/// * `connection`: Connection used to execute `SQLColumns`.
+/// * `catalog_name`: Filter result by catalog name. Accept search patterns. Use `%` to match any
+///   number of characters. Use `_` to match exactly on character. Use `\` to escape characeters.
+/// * `schema_name`: Filter result by schema. Accepts patterns in the same way as `catalog_name`.
+/// * `table_name`: Filter result by table. Accepts patterns in the same way as `catalog_name`.
+/// * `column_name`: Filter result by column. Accepts patterns in the same way as `catalog_name`.
+/// * `reader_builder`: Used to configure buffer sizes of the reader. Any schema set on the builder
+///   is ignored in favour of [`columns_schema`].
+pub fn columns_as_arrow<'c>(
+    connection: &'c Connection<'_>,
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    column_name: &str,
+    reader_builder: &OdbcReaderBuilder,
+) -> Result<OdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+    let cursor = connection
+        .columns(catalog_name, schema_name, table_name, column_name)
+        .map_err(|source| Error::CatalogFunction {
+            function: "SQLColumns",
+            source,
+        })?;
+    build_with_schema(reader_builder, columns_schema(), cursor)
+}
+
+/// Either the foreign keys of the table specified by the `fk_` arguments, or the foreign keys in
+/// other tables referring to the primary key of the table specified by the `pk_` arguments. The
+/// batches are described by [`foreign_keys_schema`].
+///
+/// See: <https://learn.microsoft.com/en-us/sql/odbc/reference/syntax/sqlforeignkeys-function>
+///
+/// # Parameters
+///
+/// * `reader_builder`: Used to configure buffer sizes of the reader. Any schema set on the builder
+///   is ignored in favour of [`foreign_keys_schema`].
+#[allow(clippy::too_many_arguments)]
+pub fn foreign_keys_as_arrow<'c>(
+    connection: &'c Connection<'_>,
+    pk_catalog_name: &str,
+    pk_schema_name: &str,
+    pk_table_name: &str,
+    fk_catalog_name: &str,
+    fk_schema_name: &str,
+    fk_table_name: &str,
+    reader_builder: &OdbcReaderBuilder,
+) -> Result<OdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+    let cursor = connection
+        .foreign_keys(
+            pk_catalog_name,
+            pk_schema_name,
+            pk_table_name,
+            fk_catalog_name,
+            fk_schema_name,
+            fk_table_name,
+        )
+        .map_err(|source| Error::CatalogFunction {
+            function: "SQLForeignKeys",
+            source,
+        })?;
+    build_with_schema(reader_builder, foreign_keys_schema(), cursor)
+}
+
+/// The columns which make up the primary key of a table, as Arrow record batches. The batches are
+/// described by [`primary_keys_schema`].
+///
+/// See: <https://learn.microsoft.com/en-us/sql/odbc/reference/syntax/sqlprimarykeys-function>
+///
+/// # Parameters
+///
+/// * `connection`: Connection used to execute `SQLPrimaryKeys`.
+/// * `catalog_name`: Catalog of the table. Pass an empty string for tables without catalog.
+/// * `schema_name`: Schema of the table. Pass an empty string for tables without schema.
+/// * `table_name`: Name of the table. Unlike for [`tables_as_arrow`] this is not a search pattern.
+/// * `reader_builder`: Used to configure buffer sizes of the reader. Any schema set on the builder
+///   is ignored in favour of [`primary_keys_schema`].
+pub fn primary_keys_as_arrow<'c>(
+    connection: &'c Connection<'_>,
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    reader_builder: &OdbcReaderBuilder,
+) -> Result<OdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+    let catalog_name = SqlText::new(catalog_name);
+    let schema_name = SqlText::new(schema_name);
+    let table_name = SqlText::new(table_name);
+    let cursor = execute_catalog_function(connection, "SQLPrimaryKeys", |statement| unsafe {
+        sql_primary_keys(
+            statement,
+            catalog_name.ptr(),
+            text_len(&catalog_name),
+            schema_name.ptr(),
+            text_len(&schema_name),
+            table_name.ptr(),
+            text_len(&table_name),
+        )
+    })?;
+    build_with_schema(reader_builder, primary_keys_schema(), cursor)
+}
+
+/// Statistics about a single table and the indexes associated with it, as Arrow record batches.
+/// The batches are described by [`statistics_schema`].
+///
+/// See: <https://learn.microsoft.com/en-us/sql/odbc/reference/syntax/sqlstatistics-function>
+///
+/// # Parameters
+///
+/// * `connection`: Connection used to execute `SQLStatistics`.
+/// * `catalog_name`: Catalog of the table. Pass an empty string for tables without catalog.
+/// * `schema_name`: Schema of the table. Pass an empty string for tables without schema.
+/// * `table_name`: Name of the table. Unlike for [`tables_as_arrow`] this is not a search pattern.
+/// * `unique_only`: Only return unique indexes (`SQL_INDEX_UNIQUE`) rather than all of them
+///   (`SQL_INDEX_ALL`).
+/// * `ensure_accurate`: Ask the driver to compute `CARDINALITY` and `PAGES` unconditionally
+///   (`SQL_ENSURE`), rather than only if they are readily available (`SQL_QUICK`).
+/// * `reader_builder`: Used to configure buffer sizes of the reader. Any schema set on the builder
+///   is ignored in favour of [`statistics_schema`].
+pub fn statistics_as_arrow<'c>(
+    connection: &'c Connection<'_>,
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    unique_only: bool,
+    ensure_accurate: bool,
+    reader_builder: &OdbcReaderBuilder,
+) -> Result<OdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+    let catalog_name = SqlText::new(catalog_name);
+    let schema_name = SqlText::new(schema_name);
+    let table_name = SqlText::new(table_name);
+    // SQL_INDEX_UNIQUE is 0, SQL_INDEX_ALL is 1
+    let unique = if unique_only { 0 } else { 1 };
+    // SQL_QUICK is 0, SQL_ENSURE is 1
+    let reserved = if ensure_accurate { 1 } else { 0 };
+    let cursor = execute_catalog_function(connection, "SQLStatistics", |statement| unsafe {
+        sql_statistics(
+            statement,
+            catalog_name.ptr(),
+            text_len(&catalog_name),
+            schema_name.ptr(),
+            text_len(&schema_name),
+            table_name.ptr(),
+            text_len(&table_name),
+            unique,
+            reserved,
+        )
+    })?;
+    build_with_schema(reader_builder, statistics_schema(), cursor)
+}
+
+/// Allocates a statement and executes a catalog function not exposed by `odbc-api` on it.
+/// `execute` is called with the raw handle of the statement.
+fn execute_catalog_function<'c>(
+    connection: &'c Connection<'_>,
+    function: &'static str,
+    execute: impl FnOnce(HStmt) -> SqlReturn,
+) -> Result<CursorImpl<StatementImpl<'c>>, Error> {
+    let to_error = |source| Error::CatalogFunction { function, source };
+    let statement = connection.preallocate().map_err(to_error)?.into_statement();
+    let ret = execute(statement.as_sys());
+    into_result(ret, function, &statement).map_err(to_error)?;
+    // Safe: Catalog functions work like a `SELECT` statement, so the statement is in cursor state.
+    Ok(unsafe { CursorImpl::new(statement) })
+}
+
+fn text_len(text: &SqlText) -> SmallInt {
+    text.len_char().try_into().unwrap()
+}
+
+// `odbc-sys` declares neither `SQLPrimaryKeys` nor `SQLStatistics`. Like `odbc-api` we use the
+// narrow functions on non windows platforms, and the wide ones on windows.
+#[cfg_attr(windows, link(name = "odbc32"))]
+#[cfg_attr(not(windows), link(name = "odbc"))]
+extern "system" {
+    #[cfg_attr(not(target_os = "windows"), link_name = "SQLPrimaryKeys")]
+    #[cfg_attr(target_os = "windows", link_name = "SQLPrimaryKeysW")]
+    fn sql_primary_keys(
+        statement_handle: HStmt,
+        catalog_name: *const SqlChar,
+        catalog_name_length: SmallInt,
+        schema_name: *const SqlChar,
+        schema_name_length: SmallInt,
+        table_name: *const SqlChar,
+        table_name_length: SmallInt,
+    ) -> SqlReturn;
+
+    #[cfg_attr(not(target_os = "windows"), link_name = "SQLStatistics")]
+    #[cfg_attr(target_os = "windows", link_name = "SQLStatisticsW")]
+    #[allow(clippy::too_many_arguments)]
+    fn sql_statistics(
+        statement_handle: HStmt,
+        catalog_name: *const SqlChar,
+        catalog_name_length: SmallInt,
+        schema_name: *const SqlChar,
+        schema_name_length: SmallInt,
+        table_name: *const SqlChar,
+        table_name_length: SmallInt,
+        unique: USmallInt,
+        reserved: USmallInt,
+    ) -> SqlReturn;
+}
+
+fn build_with_schema<'c>(
+    reader_builder: &OdbcReaderBuilder,
+    schema: Schema,
+    cursor: CursorImpl<StatementImpl<'c>>,
+) -> Result<OdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+    reader_builder
+        .clone()
+        .with_schema(Arc::new(schema))
+        .build(cursor)
+}
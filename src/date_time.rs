@@ -6,7 +6,7 @@ use arrow::{
         ArrowPrimitiveType, Time32MillisecondType, Time64MicrosecondType, Time64NanosecondType,
     },
 };
-use chrono::{DateTime, Datelike, NaiveDate, Timelike};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use odbc_api::{
     buffers::{AnySliceMut, BufferDesc, TextColumnSliceMut},
     sys::{Date, Time, Timestamp},
@@ -14,60 +14,142 @@ use odbc_api::{
 
 use crate::{odbc_writer::WriteStrategy, reader::MappingError, WriterError};
 
+/// Controls how a reader deals with dates and timestamps returned by the driver, which do not
+/// exist, e.g. MySQL's zero date `0000-00-00`, or a leap second represented with `60` seconds. See
+/// [`crate::OdbcReaderBuilder::with_on_invalid_date_time`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnInvalidDateTime {
+    /// Report a [`MappingError::InvalidDate`] or [`MappingError::InvalidTime`]. This is the
+    /// default. How the error is reported is subject to [`crate::OnConversionError`].
+    #[default]
+    Fail,
+    /// Replace the value with `NULL`. Since this is not an option for non nullable fields, these
+    /// behave like [`Self::Fail`].
+    Null,
+    /// Replace the value with a sentinel. For `Date32` fields only the date of the sentinel is
+    /// used.
+    Sentinel(NaiveDateTime),
+}
+
+impl OnInvalidDateTime {
+    /// Applies the policy to the result of converting an ODBC date or timestamp into a
+    /// [`NaiveDateTime`]. Never yields `None` if `nullable` is `false`.
+    pub fn resolve(
+        self,
+        nullable: bool,
+        date_time: Result<NaiveDateTime, MappingError>,
+    ) -> Result<Option<NaiveDateTime>, MappingError> {
+        match (self, date_time) {
+            (_, Ok(date_time)) => Ok(Some(date_time)),
+            (OnInvalidDateTime::Null, Err(_)) if nullable => Ok(None),
+            (OnInvalidDateTime::Sentinel(sentinel), Err(_)) => Ok(Some(sentinel)),
+            (_, Err(error)) => Err(error),
+        }
+    }
+}
+
+/// Controls how a reader deals with timestamps which are outside the range representable with
+/// nanoseconds precision (1677-09-21 00:12:44 to 2262-04-11 23:47:16.854775807). Data warehouses
+/// e.g. often use `9999-12-31` to mark an open end. See
+/// [`crate::OdbcReaderBuilder::with_out_of_range_timestamp`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutOfRangeTimestamp {
+    /// Report a [`MappingError::OutOfRangeTimestampNs`]. This is the default. How the error is
+    /// reported is subject to [`crate::OnConversionError`].
+    #[default]
+    Fail,
+    /// Saturate to the smallest or largest representable timestamp, i.e. `i64::MIN` or `i64::MAX`
+    /// nanoseconds since epoch.
+    Saturate,
+    /// Replace the value with `NULL`. Since this is not an option for non nullable fields, these
+    /// behave like [`Self::Fail`].
+    Null,
+    /// Infer timestamps with a precision of more than six fractional digits as microseconds rather
+    /// than nanoseconds. Microseconds cover a range of roughly 290 thousand years, so the year
+    /// `9999` poses no problem. Fractional digits beyond microseconds are truncated. Only affects
+    /// schema inference. For fields explicitly specified as nanoseconds, this behaves like
+    /// [`Self::Fail`].
+    Microseconds,
+}
+
+impl OutOfRangeTimestamp {
+    /// Nanoseconds since epoch, with the policy applied to values which are out of range. Never
+    /// yields `None` if `nullable` is `false`.
+    pub fn ns_since_epoch(
+        self,
+        nullable: bool,
+        ndt: NaiveDateTime,
+    ) -> Result<Option<i64>, MappingError> {
+        match (self, ns_since_epoch(ndt)) {
+            (_, Ok(ns)) => Ok(Some(ns)),
+            (OutOfRangeTimestamp::Saturate, Err(_)) => {
+                let ns = if ndt.and_utc().timestamp() < 0 {
+                    i64::MIN
+                } else {
+                    i64::MAX
+                };
+                Ok(Some(ns))
+            }
+            (OutOfRangeTimestamp::Null, Err(_)) if nullable => Ok(None),
+            (_, Err(error)) => Err(error),
+        }
+    }
+}
+
+/// Date at midnight. Fails if the date does not exist.
+pub fn date_to_naive(date: &Date) -> Result<NaiveDateTime, MappingError> {
+    let naive_date = NaiveDate::from_ymd_opt(date.year as i32, date.month as u32, date.day as u32)
+        .ok_or(MappingError::InvalidDate {
+            year: date.year,
+            month: date.month,
+            day: date.day,
+        })?;
+    Ok(naive_date.and_time(NaiveTime::MIN))
+}
+
+/// Fails if either the date or the time does not exist.
+pub fn timestamp_to_naive(from: &Timestamp) -> Result<NaiveDateTime, MappingError> {
+    let date = date_to_naive(&Date {
+        year: from.year,
+        month: from.month,
+        day: from.day,
+    })?;
+    let time = NaiveTime::from_hms_nano_opt(
+        from.hour as u32,
+        from.minute as u32,
+        from.second as u32,
+        from.fraction,
+    )
+    .ok_or(MappingError::InvalidTime {
+        hour: from.hour,
+        minute: from.minute,
+        second: from.second,
+        fraction: from.fraction,
+    })?;
+    Ok(date.date().and_time(time))
+}
+
 /// Transform date to days since unix epoch as i32
-pub fn days_since_epoch(date: &Date) -> i32 {
+pub fn days_since_epoch(date: NaiveDateTime) -> i32 {
     let unix_epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
-    let date =
-        NaiveDate::from_ymd_opt(date.year as i32, date.month as u32, date.day as u32).unwrap();
-    let duration = date.signed_duration_since(unix_epoch);
+    let duration = date.date().signed_duration_since(unix_epoch);
+    // Any date representable by chrono is within range of i32 days.
     duration.num_days().try_into().unwrap()
 }
 
-pub fn seconds_since_epoch(from: &Timestamp) -> i64 {
-    let ndt = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
-        .unwrap()
-        .and_hms_opt(from.hour as u32, from.minute as u32, from.second as u32)
-        .unwrap();
+pub fn seconds_since_epoch(ndt: NaiveDateTime) -> i64 {
     ndt.and_utc().timestamp()
 }
 
-pub fn ms_since_epoch(from: &Timestamp) -> i64 {
-    let ndt = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
-        .unwrap()
-        .and_hms_nano_opt(
-            from.hour as u32,
-            from.minute as u32,
-            from.second as u32,
-            from.fraction,
-        )
-        .unwrap();
+pub fn ms_since_epoch(ndt: NaiveDateTime) -> i64 {
     ndt.and_utc().timestamp_millis()
 }
 
-pub fn us_since_epoch(from: &Timestamp) -> i64 {
-    let ndt = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
-        .unwrap()
-        .and_hms_nano_opt(
-            from.hour as u32,
-            from.minute as u32,
-            from.second as u32,
-            from.fraction,
-        )
-        .unwrap();
+pub fn us_since_epoch(ndt: NaiveDateTime) -> i64 {
     ndt.and_utc().timestamp_micros()
 }
 
-pub fn ns_since_epoch(from: &Timestamp) -> Result<i64, MappingError> {
-    let ndt = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
-        .unwrap()
-        .and_hms_nano_opt(
-            from.hour as u32,
-            from.minute as u32,
-            from.second as u32,
-            from.fraction,
-        )
-        .unwrap();
-
+pub fn ns_since_epoch(ndt: NaiveDateTime) -> Result<i64, MappingError> {
     // The dates that can be represented as nanoseconds are between 1677-09-21T00:12:44.0 and
     // 2262-04-11T23:47:16.854775804
     ndt.and_utc()
@@ -230,3 +312,148 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use odbc_api::sys::{Date, Timestamp};
+
+    use crate::{reader::MappingError, OnInvalidDateTime};
+
+    use super::{date_to_naive, timestamp_to_naive};
+
+    /// MySQL's zero date
+    const ZERO_DATE: Date = Date {
+        year: 0,
+        month: 0,
+        day: 0,
+    };
+
+    /// A leap second, which chrono only represents as `59` seconds with a fraction of more than one
+    /// second.
+    const LEAP_SECOND: Timestamp = Timestamp {
+        year: 2016,
+        month: 12,
+        day: 31,
+        hour: 23,
+        minute: 59,
+        second: 60,
+        fraction: 0,
+    };
+
+    fn sentinel() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(1900, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn zero_date_is_invalid() {
+        let result = date_to_naive(&ZERO_DATE);
+
+        assert!(matches!(
+            result,
+            Err(MappingError::InvalidDate {
+                year: 0,
+                month: 0,
+                day: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn zero_date_in_timestamp_is_invalid() {
+        let timestamp = Timestamp {
+            year: 0,
+            month: 0,
+            day: 0,
+            hour: 12,
+            minute: 0,
+            second: 0,
+            fraction: 0,
+        };
+
+        let result = timestamp_to_naive(&timestamp);
+
+        assert!(matches!(result, Err(MappingError::InvalidDate { .. })));
+    }
+
+    #[test]
+    fn leap_second_is_invalid_time() {
+        let result = timestamp_to_naive(&LEAP_SECOND);
+
+        assert!(matches!(
+            result,
+            Err(MappingError::InvalidTime {
+                hour: 23,
+                minute: 59,
+                second: 60,
+                fraction: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn fail_reports_invalid_date() {
+        for nullable in [true, false] {
+            let result = OnInvalidDateTime::Fail.resolve(nullable, date_to_naive(&ZERO_DATE));
+
+            assert!(matches!(result, Err(MappingError::InvalidDate { .. })));
+        }
+    }
+
+    #[test]
+    fn null_replaces_invalid_values_of_nullable_fields() {
+        let date = OnInvalidDateTime::Null.resolve(true, date_to_naive(&ZERO_DATE));
+        let timestamp = OnInvalidDateTime::Null.resolve(true, timestamp_to_naive(&LEAP_SECOND));
+
+        assert_eq!(None, date.unwrap());
+        assert_eq!(None, timestamp.unwrap());
+    }
+
+    #[test]
+    fn null_fails_for_non_nullable_fields() {
+        let date = OnInvalidDateTime::Null.resolve(false, date_to_naive(&ZERO_DATE));
+        let timestamp = OnInvalidDateTime::Null.resolve(false, timestamp_to_naive(&LEAP_SECOND));
+
+        assert!(matches!(date, Err(MappingError::InvalidDate { .. })));
+        assert!(matches!(timestamp, Err(MappingError::InvalidTime { .. })));
+    }
+
+    #[test]
+    fn sentinel_replaces_invalid_values() {
+        let policy = OnInvalidDateTime::Sentinel(sentinel());
+        for nullable in [true, false] {
+            let date = policy.resolve(nullable, date_to_naive(&ZERO_DATE));
+            let timestamp = policy.resolve(nullable, timestamp_to_naive(&LEAP_SECOND));
+
+            assert_eq!(Some(sentinel()), date.unwrap());
+            assert_eq!(Some(sentinel()), timestamp.unwrap());
+        }
+    }
+
+    #[test]
+    fn valid_values_are_kept_by_every_policy() {
+        let valid = Timestamp {
+            second: 59,
+            ..LEAP_SECOND
+        };
+        let expected = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        let policies = [
+            OnInvalidDateTime::Fail,
+            OnInvalidDateTime::Null,
+            OnInvalidDateTime::Sentinel(sentinel()),
+        ];
+        for policy in policies {
+            for nullable in [true, false] {
+                let result = policy.resolve(nullable, timestamp_to_naive(&valid));
+
+                assert_eq!(Some(expected), result.unwrap());
+            }
+        }
+    }
+}
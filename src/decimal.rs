@@ -4,7 +4,11 @@ use arrow::{
 };
 use odbc_api::buffers::{AnySliceMut, BufferDesc};
 
-use crate::{odbc_writer::WriteStrategy, WriterError};
+use crate::{
+    numeric::{i128_to_numeric_struct, NUMERIC_STRUCT_SIZE},
+    odbc_writer::WriteStrategy,
+    WriterError,
+};
 
 pub struct NullableDecimal128AsText {
     precision: u8,
@@ -28,6 +32,50 @@ impl NullableDecimal256AsText {
     }
 }
 
+/// Binds decimals as `SQL_NUMERIC_STRUCT`. See [`crate::WriterOptions::decimals_as_numeric_struct`].
+pub struct NullableDecimal128AsNumericStruct {
+    precision: u8,
+    scale: i8,
+}
+
+impl NullableDecimal128AsNumericStruct {
+    pub fn new(precision: u8, scale: i8) -> Self {
+        Self { precision, scale }
+    }
+}
+
+impl WriteStrategy for NullableDecimal128AsNumericStruct {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Binary {
+            length: NUMERIC_STRUCT_SIZE,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        column_buf: AnySliceMut<'_>,
+        array: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+        let mut to = column_buf.as_bin_view().unwrap();
+
+        for (index, cell) in from.iter().enumerate() {
+            let numeric =
+                cell.map(|value| i128_to_numeric_struct(value, self.precision, self.scale));
+            to.set_cell(
+                index + param_offset,
+                numeric.as_ref().map(|bytes| bytes.as_slice()),
+            )
+        }
+        Ok(())
+    }
+
+    fn numeric_precision_and_scale(&self) -> Option<(u8, i8)> {
+        Some((self.precision, self.scale))
+    }
+}
+
 /// Length of a text representation of a decimal
 fn len_text(scale: i8, precision: u8) -> usize {
     match scale {
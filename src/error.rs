@@ -35,6 +35,31 @@ pub enum Error {
         max_bytes_per_batch: usize,
         bytes_per_row: usize,
     },
+    /// Failure to execute an ODBC catalog function (e.g. `SQLTables`) in order to obtain a cursor.
+    #[error("Failed to execute ODBC catalog function {function}.\n{source}")]
+    CatalogFunction {
+        /// Name of the ODBC function, e.g. `SQLTables`.
+        function: &'static str,
+        source: odbc_api::Error,
+    },
+    /// Failure to connect to the data source or to execute the query for one partition of a
+    /// [`crate::PartitionedReader`].
+    #[error("Failed to connect or to execute the query for partition {partition}.\n{source}")]
+    PartitionFailed {
+        /// Zero based index of the partition
+        partition: usize,
+        source: odbc_api::Error,
+    },
+    /// The [`crate::PartitionSpec`] passed to [`crate::PartitionedReader::new`] describes no
+    /// partition at all, i.e. zero `num_partitions` or an empty list of predicates.
+    #[error("The partition spec must describe at least one partition.")]
+    NoPartitions,
+    /// The query for a partition of a [`crate::PartitionedReader`] did not produce a result set.
+    #[error("The query for partition {partition} did not produce a result set.")]
+    PartitionWithoutResultSet {
+        /// Zero based index of the partition
+        partition: usize,
+    },
     /// We use UTF-16 encoding on windows by default. Since UTF-8 locals on windows system can not
     /// be expected to be the default. Since we use wide methods the ODBC standard demands the
     /// encoding to be UTF-16.
@@ -0,0 +1,746 @@
+use std::{
+    fmt::Write,
+    marker::PhantomData,
+    mem::{offset_of, size_of},
+};
+
+use arrow::{
+    array::{Array, PrimitiveArray},
+    datatypes::{
+        ArrowPrimitiveType, DataType, DurationMicrosecondType, DurationMillisecondType,
+        DurationNanosecondType, DurationSecondType, IntervalDayTimeType, IntervalMonthDayNanoType,
+        IntervalUnit, IntervalYearMonthType, TimeUnit,
+    },
+};
+use odbc_api::{
+    buffers::{AnySliceMut, BufferDesc},
+    handles::Descriptor,
+    sys::{
+        CDataType, Desc, HStmt, Interval, IntervalStruct, Len, ParamType, Pointer,
+        SQLBindParameter, SQLSetDescFieldW, SqlDataType, StatementAttribute,
+    },
+    Error,
+};
+
+use crate::{
+    numeric::{descriptor, into_result, pointer_field, RawStatement},
+    odbc_writer::WriteStrategy,
+    WriterError,
+};
+
+/// Size of an `SQL_INTERVAL_STRUCT` in bytes. Like numeric structs, interval structs are bound
+/// using binary buffers of this size, and altering the C-Type in the application descriptor
+/// afterwards.
+pub const INTERVAL_STRUCT_SIZE: usize = size_of::<IntervalStruct>();
+
+const INTERVAL_TYPE: usize = offset_of!(IntervalStruct, interval_type);
+const INTERVAL_SIGN: usize = offset_of!(IntervalStruct, interval_sign);
+/// The union holds either `year`, `month` or `day`, `hour`, `minute`, `second`, `fraction` as
+/// consecutive 32 Bit unsigned integers.
+const INTERVAL_VALUE: usize = offset_of!(IntervalStruct, interval_value);
+
+/// Number of digits of the leading field, i.e. years or days. Nine digits are the most we can hold
+/// in the unsigned 32 Bit integers of the interval struct.
+const LEADING_PRECISION: i16 = 9;
+/// Digits of the fractional seconds. We always transfer nanoseconds.
+const FRACTION_PRECISION: i16 = 9;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const NANOS_PER_MINUTE: u64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: u64 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: u64 = 24 * NANOS_PER_HOUR;
+
+/// The two families of SQL interval types. ODBC converts between all interval types of the same
+/// family, so we only ever bind the most comprehensive type of each family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalKind {
+    /// `SQL_INTERVAL_YEAR`, `SQL_INTERVAL_MONTH` and `SQL_INTERVAL_YEAR_TO_MONTH`.
+    YearToMonth,
+    /// `SQL_INTERVAL_DAY`, `SQL_INTERVAL_DAY_TO_SECOND`, `SQL_INTERVAL_MINUTE` and all other types
+    /// without a year or month field.
+    DayToSecond,
+}
+
+impl IntervalKind {
+    /// `None` if `sql_type` is not an interval type.
+    pub fn from_sql_data_type(sql_type: SqlDataType) -> Option<Self> {
+        // The codes of the interval SQL types match the ones of the C types.
+        match sql_type.0 {
+            101 | 102 | 107 => Some(IntervalKind::YearToMonth),
+            103..=106 | 108..=113 => Some(IntervalKind::DayToSecond),
+            _ => None,
+        }
+    }
+
+    fn c_data_type(self) -> CDataType {
+        match self {
+            IntervalKind::YearToMonth => CDataType::IntervalYearToMonth,
+            IntervalKind::DayToSecond => CDataType::IntervalDayToSecond,
+        }
+    }
+
+    fn sql_data_type(self) -> SqlDataType {
+        SqlDataType(self.c_data_type() as i16)
+    }
+
+    /// Length of the longest literal, e.g. `-999999999 23:59:59.999999999`. ODBC expects this as
+    /// column size of interval parameters.
+    fn column_size(self) -> usize {
+        match self {
+            IntervalKind::YearToMonth => 13,
+            IntervalKind::DayToSecond => 29,
+        }
+    }
+
+    fn decimal_digits(self) -> i16 {
+        match self {
+            IntervalKind::YearToMonth => 0,
+            IntervalKind::DayToSecond => FRACTION_PRECISION,
+        }
+    }
+}
+
+/// Changes the C-Type of an already bound binary column to an `SQL_INTERVAL_STRUCT` of `kind`.
+///
+/// # Safety
+///
+/// `statement` must be a valid statement handle. The column must have been bound to a binary buffer
+/// with elements of [`INTERVAL_STRUCT_SIZE`].
+pub unsafe fn bind_column_as_interval(
+    statement: HStmt,
+    column_number: u16,
+    kind: IntervalKind,
+) -> Result<(), Error> {
+    let record = column_number as i16;
+    let mut ard = descriptor(statement, StatementAttribute::AppRowDesc)?;
+    let data_ptr = pointer_field(&ard, record, Desc::DataPtr)?;
+    set_interval_type(&mut ard, record, kind)?;
+    ard.set_data_ptr(record, data_ptr).into_result(&ard)?;
+    Ok(())
+}
+
+/// Binds the buffer of an already bound binary parameter again, as `SQL_INTERVAL_STRUCT` of
+/// `kind`. The parameter is described to the data source as interval of the same kind.
+///
+/// # Safety
+///
+/// `statement` must be a valid statement handle. The parameter must have been bound to a binary
+/// buffer with elements of [`INTERVAL_STRUCT_SIZE`].
+pub unsafe fn bind_parameter_as_interval(
+    statement: HStmt,
+    parameter_number: u16,
+    kind: IntervalKind,
+) -> Result<(), Error> {
+    let record = parameter_number as i16;
+    let mut apd = descriptor(statement, StatementAttribute::AppParamDesc)?;
+    let data_ptr = pointer_field(&apd, record, Desc::DataPtr)?;
+    let indicator_ptr = pointer_field(&apd, record, Desc::IndicatorPtr)?;
+    let ret = SQLBindParameter(
+        statement,
+        parameter_number,
+        ParamType::Input,
+        kind.c_data_type(),
+        kind.sql_data_type(),
+        kind.column_size(),
+        kind.decimal_digits(),
+        data_ptr,
+        INTERVAL_STRUCT_SIZE as Len,
+        indicator_ptr as *mut Len,
+    );
+    into_result(ret, "SQLBindParameter", &RawStatement(statement))?;
+    // Binding the parameter leaves the application descriptor with the default precisions, which
+    // would truncate leading fields to two and fractions to six digits.
+    set_interval_type(&mut apd, record, kind)?;
+    apd.set_data_ptr(record, data_ptr).into_result(&apd)?;
+    Ok(())
+}
+
+/// Sets the concise type and the precisions of an application descriptor record. Unbinds the data
+/// pointer.
+unsafe fn set_interval_type(
+    descriptor: &mut Descriptor<'_>,
+    record: i16,
+    kind: IntervalKind,
+) -> Result<(), Error> {
+    // Setting the concise type also sets the verbose type and the interval code.
+    set_small_int_field(
+        descriptor,
+        record,
+        Desc::ConciseType,
+        kind.c_data_type() as i16,
+    )?;
+    set_small_int_field(
+        descriptor,
+        record,
+        Desc::DatetimeIntervalPrecision,
+        LEADING_PRECISION,
+    )?;
+    if kind == IntervalKind::DayToSecond {
+        descriptor
+            .set_precision(record, FRACTION_PRECISION)
+            .into_result(descriptor)?;
+    }
+    Ok(())
+}
+
+unsafe fn set_small_int_field(
+    descriptor: &mut Descriptor<'_>,
+    record: i16,
+    field: Desc,
+    value: i16,
+) -> Result<(), Error> {
+    // Integer values are passed in place of the pointer.
+    let ret = SQLSetDescFieldW(
+        descriptor.as_sys(),
+        record,
+        field,
+        value as isize as Pointer,
+        0,
+    );
+    into_result(ret, "SQLSetDescField", descriptor)
+}
+
+/// Bytes of an `SQL_INTERVAL_STRUCT` holding a year to month interval of `months`.
+pub fn months_to_interval_struct(months: i32) -> [u8; INTERVAL_STRUCT_SIZE] {
+    let mut bytes = interval_struct_header(Interval::YearToMonth, months.is_negative());
+    let months = months.unsigned_abs();
+    set_interval_field(&mut bytes, 0, months / 12);
+    set_interval_field(&mut bytes, 1, months % 12);
+    bytes
+}
+
+/// Bytes of an `SQL_INTERVAL_STRUCT` holding a day to second interval of `days` and `nanoseconds`.
+/// Both are added up, so they may have different signs.
+pub fn days_and_nanos_to_interval_struct(
+    days: i32,
+    nanoseconds: i64,
+) -> [u8; INTERVAL_STRUCT_SIZE] {
+    let total = days as i128 * NANOS_PER_DAY as i128 + nanoseconds as i128;
+    let mut bytes = interval_struct_header(Interval::DayToSecond, total.is_negative());
+    let total = total.unsigned_abs();
+    // At most `2^31` days, which fit into the unsigned 32 Bit field.
+    set_interval_field(&mut bytes, 0, (total / NANOS_PER_DAY as u128) as u32);
+    // The remainder is less than a day, so it fits into 64 Bit.
+    let total = (total % NANOS_PER_DAY as u128) as u64;
+    set_interval_field(&mut bytes, 1, (total / NANOS_PER_HOUR) as u32);
+    set_interval_field(
+        &mut bytes,
+        2,
+        (total % NANOS_PER_HOUR / NANOS_PER_MINUTE) as u32,
+    );
+    set_interval_field(
+        &mut bytes,
+        3,
+        (total % NANOS_PER_MINUTE / NANOS_PER_SECOND) as u32,
+    );
+    set_interval_field(&mut bytes, 4, (total % NANOS_PER_SECOND) as u32);
+    bytes
+}
+
+/// Interprets the bytes of an `SQL_INTERVAL_STRUCT` holding a year to month interval. `None` if
+/// the number of months does not fit into 32 Bit.
+pub fn interval_struct_to_months(bytes: &[u8]) -> Option<i32> {
+    let years = interval_field(bytes, 0) as i64;
+    let months = interval_field(bytes, 1) as i64;
+    let months = years * 12 + months;
+    let months = if interval_is_negative(bytes) {
+        -months
+    } else {
+        months
+    };
+    months.try_into().ok()
+}
+
+/// Interprets the bytes of an `SQL_INTERVAL_STRUCT` holding a day to second interval. `None` if the
+/// days do not fit into 32 Bit or the remaining time not into 64 Bit of nanoseconds.
+pub fn interval_struct_to_days_and_nanos(bytes: &[u8]) -> Option<(i32, i64)> {
+    let days = interval_field(bytes, 0) as i64;
+    let nanos = interval_field(bytes, 1) as i128 * NANOS_PER_HOUR as i128
+        + interval_field(bytes, 2) as i128 * NANOS_PER_MINUTE as i128
+        + interval_field(bytes, 3) as i128 * NANOS_PER_SECOND as i128
+        + interval_field(bytes, 4) as i128;
+    // Negate before narrowing, so `i32::MIN` days and `i64::MIN` nanoseconds are representable.
+    let (days, nanos) = if interval_is_negative(bytes) {
+        (-days, -nanos)
+    } else {
+        (days, nanos)
+    };
+    Some((days.try_into().ok()?, nanos.try_into().ok()?))
+}
+
+/// Renders the bytes of an `SQL_INTERVAL_STRUCT` as SQL interval literal, e.g. `-1-02` for year to
+/// month or `3 04:05:06.000000007` for day to second intervals. Unlike
+/// [`interval_struct_to_months`] and [`interval_struct_to_days_and_nanos`] this works for any value,
+/// so we can report values which are out of range.
+pub fn interval_struct_to_string(bytes: &[u8]) -> String {
+    let sign = if interval_is_negative(bytes) { "-" } else { "" };
+    let interval_type =
+        i32::from_ne_bytes(bytes[INTERVAL_TYPE..INTERVAL_TYPE + 4].try_into().unwrap());
+    let field = |index| interval_field(bytes, index);
+    if interval_type == Interval::YearToMonth as i32 {
+        format!("{sign}{}-{:02}", field(0), field(1))
+    } else {
+        format!(
+            "{sign}{} {:02}:{:02}:{:02}.{:09}",
+            field(0),
+            field(1),
+            field(2),
+            field(3),
+            field(4)
+        )
+    }
+}
+
+fn interval_struct_header(interval_type: Interval, negative: bool) -> [u8; INTERVAL_STRUCT_SIZE] {
+    let mut bytes = [0; INTERVAL_STRUCT_SIZE];
+    bytes[INTERVAL_TYPE..INTERVAL_TYPE + 4].copy_from_slice(&(interval_type as i32).to_ne_bytes());
+    // SQL_TRUE if negative, SQL_FALSE otherwise
+    bytes[INTERVAL_SIGN..INTERVAL_SIGN + 2].copy_from_slice(&i16::from(negative).to_ne_bytes());
+    bytes
+}
+
+fn interval_is_negative(bytes: &[u8]) -> bool {
+    i16::from_ne_bytes([bytes[INTERVAL_SIGN], bytes[INTERVAL_SIGN + 1]]) != 0
+}
+
+fn interval_field(bytes: &[u8], index: usize) -> u32 {
+    let start = INTERVAL_VALUE + 4 * index;
+    u32::from_ne_bytes(bytes[start..start + 4].try_into().unwrap())
+}
+
+fn set_interval_field(bytes: &mut [u8], index: usize, value: u32) {
+    let start = INTERVAL_VALUE + 4 * index;
+    bytes[start..start + 4].copy_from_slice(&value.to_ne_bytes());
+}
+
+/// Formats an interval as ISO 8601 duration, e.g. `P1Y2M3DT4H5M6.7S`. Components which are zero
+/// are omitted. Each component carries its own sign, e.g. `P1M-2D`.
+fn write_iso_8601(months: i32, days: i32, nanoseconds: i64, text: &mut String) {
+    text.push('P');
+    let (years, months) = (months / 12, months % 12);
+    if years != 0 {
+        write!(text, "{years}Y").unwrap();
+    }
+    if months != 0 {
+        write!(text, "{months}M").unwrap();
+    }
+    if days != 0 {
+        write!(text, "{days}D").unwrap();
+    }
+    if nanoseconds != 0 || text.len() == 1 {
+        text.push('T');
+        let sign = if nanoseconds.is_negative() { "-" } else { "" };
+        let nanoseconds = nanoseconds.unsigned_abs();
+        let hours = nanoseconds / NANOS_PER_HOUR;
+        let minutes = nanoseconds % NANOS_PER_HOUR / NANOS_PER_MINUTE;
+        let seconds = nanoseconds % NANOS_PER_MINUTE / NANOS_PER_SECOND;
+        let fraction = nanoseconds % NANOS_PER_SECOND;
+        if hours != 0 {
+            write!(text, "{sign}{hours}H").unwrap();
+        }
+        if minutes != 0 {
+            write!(text, "{sign}{minutes}M").unwrap();
+        }
+        if seconds != 0 || fraction != 0 || nanoseconds == 0 {
+            write!(text, "{sign}{seconds}").unwrap();
+            if fraction != 0 {
+                let fraction = format!("{fraction:09}");
+                write!(text, ".{}", fraction.trim_end_matches('0')).unwrap();
+            }
+            text.push('S');
+        }
+    }
+}
+
+fn ticks_per_second(unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    }
+}
+
+/// Formats a duration as SQL `DAY TO SECOND` interval literal, e.g. `-1 02:03:04.500`. The number
+/// of fractional digits is determined by `unit`.
+fn write_day_to_second(value: i64, unit: TimeUnit, text: &mut String) {
+    let ticks_per_second = ticks_per_second(&unit) as u64;
+    let fraction_digits = ticks_per_second.ilog10() as usize;
+    if value.is_negative() {
+        text.push('-');
+    }
+    let ticks = value.unsigned_abs();
+    let total_seconds = ticks / ticks_per_second;
+    let fraction = ticks % ticks_per_second;
+    let days = total_seconds / (24 * 60 * 60);
+    let hours = total_seconds % (24 * 60 * 60) / (60 * 60);
+    let minutes = total_seconds % (60 * 60) / 60;
+    let seconds = total_seconds % 60;
+    write!(text, "{days} {hours:02}:{minutes:02}:{seconds:02}").unwrap();
+    if fraction_digits != 0 {
+        write!(text, ".{fraction:0fraction_digits$}").unwrap();
+    }
+}
+
+/// Converts a duration `value` of unit `from` into unit `to`. Converting into a coarser unit
+/// truncates towards zero, converting into a finer unit fails if the result exceeds 64 Bit.
+pub fn convert_duration(value: i64, from: &TimeUnit, to: &TimeUnit) -> Result<i64, WriterError> {
+    let from_ticks = ticks_per_second(from);
+    let to_ticks = ticks_per_second(to);
+    if to_ticks >= from_ticks {
+        value
+            .checked_mul(to_ticks / from_ticks)
+            .ok_or_else(|| WriterError::ValueOutOfRange {
+                value: value.to_string(),
+                arrow_type: DataType::Duration(from.clone()),
+                odbc_type: "BIGINT",
+            })
+    } else {
+        Ok(value / (from_ticks / to_ticks))
+    }
+}
+
+/// Arrow interval and duration types, which can be written as text.
+pub trait IntervalText: ArrowPrimitiveType {
+    /// Upper bound for the length of the text representation.
+    const MAX_STR_LEN: usize;
+
+    /// Intervals are formatted as ISO 8601 durations, durations as SQL `DAY TO SECOND` interval
+    /// literals.
+    fn write_text(value: Self::Native, text: &mut String);
+}
+
+impl IntervalText for IntervalYearMonthType {
+    // P-178956970Y-8M
+    const MAX_STR_LEN: usize = 16;
+
+    fn write_text(value: i32, text: &mut String) {
+        write_iso_8601(value, 0, 0, text)
+    }
+}
+
+impl IntervalText for IntervalDayTimeType {
+    // P-2147483648DT-596H-31M-23.648S
+    const MAX_STR_LEN: usize = 32;
+
+    fn write_text(value: i64, text: &mut String) {
+        let (days, milliseconds) = IntervalDayTimeType::to_parts(value);
+        write_iso_8601(0, days, milliseconds as i64 * 1_000_000, text)
+    }
+}
+
+impl IntervalText for IntervalMonthDayNanoType {
+    // P-178956970Y-8M-2147483648DT-2562047H-47M-16.854775808S
+    const MAX_STR_LEN: usize = 56;
+
+    fn write_text(value: i128, text: &mut String) {
+        let (months, days, nanoseconds) = IntervalMonthDayNanoType::to_parts(value);
+        write_iso_8601(months, days, nanoseconds, text)
+    }
+}
+
+impl IntervalText for DurationSecondType {
+    // -106751991167300 15:30:08
+    const MAX_STR_LEN: usize = 25;
+
+    fn write_text(value: i64, text: &mut String) {
+        write_day_to_second(value, TimeUnit::Second, text)
+    }
+}
+
+impl IntervalText for DurationMillisecondType {
+    // -106751991167 07:12:55.808
+    const MAX_STR_LEN: usize = 26;
+
+    fn write_text(value: i64, text: &mut String) {
+        write_day_to_second(value, TimeUnit::Millisecond, text)
+    }
+}
+
+impl IntervalText for DurationMicrosecondType {
+    // -106751991 04:00:54.775808
+    const MAX_STR_LEN: usize = 26;
+
+    fn write_text(value: i64, text: &mut String) {
+        write_day_to_second(value, TimeUnit::Microsecond, text)
+    }
+}
+
+impl IntervalText for DurationNanosecondType {
+    // -106751 23:47:16.854775808
+    const MAX_STR_LEN: usize = 26;
+
+    fn write_text(value: i64, text: &mut String) {
+        write_day_to_second(value, TimeUnit::Nanosecond, text)
+    }
+}
+
+/// Writes intervals and durations as text. See [`crate::IntervalFormat::Iso8601`] and
+/// [`crate::DurationFormat::IntervalText`].
+pub struct NullableIntervalAsText<P> {
+    _phantom: PhantomData<P>,
+}
+
+impl<P> NullableIntervalAsText<P> {
+    pub fn new() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<P> WriteStrategy for NullableIntervalAsText<P>
+where
+    P: IntervalText,
+{
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            max_str_len: P::MAX_STR_LEN,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        column_buf: AnySliceMut<'_>,
+        array: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = array.as_any().downcast_ref::<PrimitiveArray<P>>().unwrap();
+        let mut to = column_buf.as_text_view().unwrap();
+        let mut text = String::with_capacity(P::MAX_STR_LEN);
+        for (index, cell) in from.iter().enumerate() {
+            if let Some(value) = cell {
+                text.clear();
+                P::write_text(value, &mut text);
+                to.set_cell(index + param_offset, Some(text.as_bytes()))
+            } else {
+                to.set_cell(index + param_offset, None)
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Arrow interval types, which can be written as `SQL_INTERVAL_STRUCT`.
+pub trait IntervalStructPrimitive: ArrowPrimitiveType {
+    const KIND: IntervalKind;
+
+    fn to_interval_struct(value: Self::Native) -> Result<[u8; INTERVAL_STRUCT_SIZE], WriterError>;
+}
+
+impl IntervalStructPrimitive for IntervalYearMonthType {
+    const KIND: IntervalKind = IntervalKind::YearToMonth;
+
+    fn to_interval_struct(value: i32) -> Result<[u8; INTERVAL_STRUCT_SIZE], WriterError> {
+        Ok(months_to_interval_struct(value))
+    }
+}
+
+impl IntervalStructPrimitive for IntervalDayTimeType {
+    const KIND: IntervalKind = IntervalKind::DayToSecond;
+
+    fn to_interval_struct(value: i64) -> Result<[u8; INTERVAL_STRUCT_SIZE], WriterError> {
+        let (days, milliseconds) = IntervalDayTimeType::to_parts(value);
+        Ok(days_and_nanos_to_interval_struct(
+            days,
+            milliseconds as i64 * 1_000_000,
+        ))
+    }
+}
+
+impl IntervalStructPrimitive for IntervalMonthDayNanoType {
+    const KIND: IntervalKind = IntervalKind::DayToSecond;
+
+    fn to_interval_struct(value: i128) -> Result<[u8; INTERVAL_STRUCT_SIZE], WriterError> {
+        let (months, days, nanoseconds) = IntervalMonthDayNanoType::to_parts(value);
+        // The length of a month depends on the date it is added to. So we can not express it in
+        // days.
+        if months != 0 {
+            return Err(WriterError::ValueOutOfRange {
+                value: format!("{months} months {days} days {nanoseconds} nanoseconds"),
+                arrow_type: DataType::Interval(IntervalUnit::MonthDayNano),
+                odbc_type: "INTERVAL DAY TO SECOND",
+            });
+        }
+        Ok(days_and_nanos_to_interval_struct(days, nanoseconds))
+    }
+}
+
+/// Binds intervals as `SQL_INTERVAL_STRUCT`. See [`crate::IntervalFormat::SqlInterval`].
+pub struct NullableIntervalAsStruct<P> {
+    _phantom: PhantomData<P>,
+}
+
+impl<P> NullableIntervalAsStruct<P> {
+    pub fn new() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<P> WriteStrategy for NullableIntervalAsStruct<P>
+where
+    P: IntervalStructPrimitive,
+{
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Binary {
+            length: INTERVAL_STRUCT_SIZE,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        column_buf: AnySliceMut<'_>,
+        array: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = array.as_any().downcast_ref::<PrimitiveArray<P>>().unwrap();
+        let mut to = column_buf.as_bin_view().unwrap();
+        for (index, cell) in from.iter().enumerate() {
+            let interval = cell.map(P::to_interval_struct).transpose()?;
+            to.set_cell(
+                index + param_offset,
+                interval.as_ref().map(|bytes| bytes.as_slice()),
+            )
+        }
+        Ok(())
+    }
+
+    fn interval_kind(&self) -> Option<IntervalKind> {
+        Some(P::KIND)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{IntervalDayTimeType, IntervalMonthDayNanoType};
+
+    use super::{
+        days_and_nanos_to_interval_struct, interval_struct_to_days_and_nanos,
+        interval_struct_to_months, interval_struct_to_string, months_to_interval_struct,
+        IntervalText, INTERVAL_SIGN, NANOS_PER_DAY,
+    };
+
+    fn iso_8601<P: IntervalText>(value: P::Native) -> String {
+        let mut text = String::new();
+        P::write_text(value, &mut text);
+        text
+    }
+
+    #[test]
+    fn months_round_trip() {
+        for months in [0, 1, -1, 14, -14, i32::MAX, i32::MIN] {
+            let bytes = months_to_interval_struct(months);
+
+            assert_eq!(Some(months), interval_struct_to_months(&bytes));
+        }
+    }
+
+    #[test]
+    fn days_and_nanos_round_trip() {
+        let values = [
+            (0, 0),
+            (1, 1),
+            (-1, -1),
+            (3, 4 * 3_600_000_000_000 + 5),
+            (-3, -(4 * 3_600_000_000_000 + 5)),
+            (i32::MAX, NANOS_PER_DAY as i64 - 1),
+            (i32::MIN, 0),
+            (i32::MIN, -(NANOS_PER_DAY as i64 - 1)),
+        ];
+        for (days, nanos) in values {
+            let bytes = days_and_nanos_to_interval_struct(days, nanos);
+
+            assert_eq!(
+                Some((days, nanos)),
+                interval_struct_to_days_and_nanos(&bytes)
+            );
+        }
+    }
+
+    #[test]
+    fn days_and_nanos_with_different_signs_are_added_up() {
+        // One day minus one nanosecond
+        let bytes = days_and_nanos_to_interval_struct(1, -1);
+
+        assert_eq!(
+            Some((0, NANOS_PER_DAY as i64 - 1)),
+            interval_struct_to_days_and_nanos(&bytes)
+        );
+    }
+
+    #[test]
+    fn nanos_exceeding_a_day_carry_over_into_days() {
+        let bytes = days_and_nanos_to_interval_struct(0, i64::MIN);
+
+        let (days, nanos) = interval_struct_to_days_and_nanos(&bytes).unwrap();
+
+        assert_eq!(
+            i64::MIN as i128,
+            days as i128 * NANOS_PER_DAY as i128 + nanos as i128
+        );
+    }
+
+    #[test]
+    fn days_out_of_range() {
+        // 2^31 days, which is only representable as negative `i32`
+        let mut bytes = days_and_nanos_to_interval_struct(i32::MIN, 0);
+        bytes[INTERVAL_SIGN..INTERVAL_SIGN + 2].copy_from_slice(&0i16.to_ne_bytes());
+
+        assert_eq!(None, interval_struct_to_days_and_nanos(&bytes));
+    }
+
+    #[test]
+    fn render_interval_structs_as_sql_literals() {
+        assert_eq!(
+            "-1-02",
+            interval_struct_to_string(&months_to_interval_struct(-14))
+        );
+        assert_eq!(
+            "3 04:05:06.000000007",
+            interval_struct_to_string(&days_and_nanos_to_interval_struct(
+                3,
+                4 * 3_600_000_000_000 + 5 * 60_000_000_000 + 6_000_000_007
+            ))
+        );
+    }
+
+    #[test]
+    fn iso_8601_zero() {
+        assert_eq!("PT0S", iso_8601::<IntervalMonthDayNanoType>(0));
+        assert_eq!("PT0S", iso_8601::<IntervalDayTimeType>(0));
+    }
+
+    #[test]
+    fn iso_8601_negative_components() {
+        let value = IntervalMonthDayNanoType::make_value(-14, -3, -4_500_000_000);
+
+        assert_eq!(
+            "P-1Y-2M-3DT-4.5S",
+            iso_8601::<IntervalMonthDayNanoType>(value)
+        );
+    }
+
+    #[test]
+    fn iso_8601_mixed_signs() {
+        let value = IntervalMonthDayNanoType::make_value(1, -2, 3_600_000_000_000);
+
+        assert_eq!("P1M-2DT1H", iso_8601::<IntervalMonthDayNanoType>(value));
+    }
+
+    #[test]
+    fn iso_8601_extremes() {
+        let day_time = IntervalDayTimeType::make_value(i32::MIN, i32::MIN);
+        let month_day_nano = IntervalMonthDayNanoType::make_value(i32::MIN, i32::MIN, i64::MIN);
+
+        assert_eq!(
+            "P-2147483648DT-596H-31M-23.648S",
+            iso_8601::<IntervalDayTimeType>(day_time)
+        );
+        assert_eq!(
+            "P-178956970Y-8M-2147483648DT-2562047H-47M-16.854775808S",
+            iso_8601::<IntervalMonthDayNanoType>(month_day_nano)
+        );
+    }
+}
@@ -41,9 +41,12 @@
 //!     Ok(())
 //! }
 //! ```
+mod catalog;
 mod date_time;
 mod decimal;
 mod error;
+mod interval;
+mod numeric;
 mod odbc_writer;
 mod quirks;
 mod reader;
@@ -55,11 +58,26 @@ pub use arrow;
 pub use odbc_api;
 
 pub use self::{
+    catalog::{
+        columns_as_arrow, columns_schema, foreign_keys_as_arrow, foreign_keys_schema,
+        primary_keys_as_arrow, primary_keys_schema, statistics_as_arrow, statistics_schema,
+        tables_as_arrow, tables_schema,
+    },
+    date_time::{OnInvalidDateTime, OutOfRangeTimestamp},
     error::Error,
-    odbc_writer::{insert_into_table, insert_statement_from_schema, OdbcWriter, WriterError},
+    odbc_writer::{
+        create_table_statement_from_schema, flatten_schema, insert_into_table,
+        insert_statement_from_schema, DurationFormat, IntervalFormat, OdbcWriter, SqlDialect,
+        WriterError, WriterOptions,
+    },
     quirks::Quirks,
     reader::{
-        BufferAllocationOptions, ColumnFailure, ConcurrentOdbcReader, OdbcReader, OdbcReaderBuilder,
+        rejects_schema, BatchStats, BufferAllocationOptions, CancelHandle, Cancelled,
+        ColumnFailure, ConcurrentOdbcReader, ConversionError, MappingError, MergedReader,
+        OdbcReader, OdbcReaderBuilder, OnConversionError, PartitionCursor, PartitionSpec,
+        PartitionedReader, ReaderStats, Reject, StructFormat,
+    },
+    schema::{
+        arrow_schema_from, arrow_schema_with_column_names, ColumnNameCase, ColumnNameOptions,
     },
-    schema::arrow_schema_from,
 };
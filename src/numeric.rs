@@ -0,0 +1,296 @@
+use std::ptr::null_mut;
+
+use odbc_api::{
+    handles::{AsHandle, Descriptor, SqlResult},
+    sys::{
+        CDataType, Desc, HDesc, HStmt, Handle, HandleType, Len, ParamType, Pointer,
+        SQLBindParameter, SQLGetDescFieldW, SQLGetStmtAttr, SqlDataType, SqlReturn,
+        StatementAttribute, IS_POINTER,
+    },
+    Error,
+};
+
+/// Size of an `SQL_NUMERIC_STRUCT` in bytes. `odbc-api` offers no buffers for numeric structs, so we
+/// bind binary buffers with elements of this size and alter the C-Type in the application
+/// descriptor afterwards.
+pub const NUMERIC_STRUCT_SIZE: usize = std::mem::size_of::<odbc_api::sys::Numeric>();
+
+/// Layout: precision (1 Byte), scale (1 Byte), sign (1 Byte, 1 if positive), little endian mantissa
+/// (16 Bytes).
+const MANTISSA_START: usize = 3;
+
+/// Interprets the bytes of an `SQL_NUMERIC_STRUCT` as a decimal with `scale`. Should the driver
+/// have chosen a different scale, the value is rescaled. `None` if the value does not fit into an
+/// `i128`, or rescaling it would drop non zero digits.
+pub fn numeric_struct_to_i128(bytes: &[u8], scale: i8) -> Option<i128> {
+    let source_scale = bytes[1] as i8;
+    let positive = bytes[2] == 1;
+    let mantissa: [u8; 16] = bytes[MANTISSA_START..NUMERIC_STRUCT_SIZE]
+        .try_into()
+        .expect("ODBC driver must fill SQL_NUMERIC_STRUCT completely");
+    let mut value = i128::try_from(u128::from_le_bytes(mantissa)).ok()?;
+    let scale_difference = i16::from(scale) - i16::from(source_scale);
+    let factor = 10i128.checked_pow(u32::from(scale_difference.unsigned_abs()))?;
+    if scale_difference > 0 {
+        value = value.checked_mul(factor)?;
+    } else if scale_difference < 0 {
+        if value % factor != 0 {
+            return None;
+        }
+        value /= factor;
+    }
+    Some(if positive { value } else { -value })
+}
+
+/// Renders the bytes of an `SQL_NUMERIC_STRUCT` as decimal text with the scale chosen by the
+/// driver, e.g. `-123.45`. Unlike [`numeric_struct_to_i128`] this works for any mantissa, so we can
+/// report values which are out of range.
+pub fn numeric_struct_to_string(bytes: &[u8]) -> String {
+    let scale = bytes[1] as i8;
+    let positive = bytes[2] == 1;
+    let mantissa: [u8; 16] = bytes[MANTISSA_START..NUMERIC_STRUCT_SIZE]
+        .try_into()
+        .expect("ODBC driver must fill SQL_NUMERIC_STRUCT completely");
+    let mut digits = u128::from_le_bytes(mantissa).to_string();
+    if scale < 0 {
+        digits.push_str(&"0".repeat(scale.unsigned_abs().into()));
+    } else if scale > 0 {
+        let scale = scale as usize;
+        if digits.len() <= scale {
+            digits.insert_str(0, &"0".repeat(scale + 1 - digits.len()));
+        }
+        digits.insert(digits.len() - scale, '.');
+    }
+    if positive {
+        digits
+    } else {
+        format!("-{digits}")
+    }
+}
+
+/// Bytes of an `SQL_NUMERIC_STRUCT` representing `value` with `precision` and `scale`.
+pub fn i128_to_numeric_struct(value: i128, precision: u8, scale: i8) -> [u8; NUMERIC_STRUCT_SIZE] {
+    let mut bytes = [0; NUMERIC_STRUCT_SIZE];
+    bytes[0] = precision;
+    bytes[1] = scale as u8;
+    bytes[2] = u8::from(!value.is_negative());
+    bytes[MANTISSA_START..].copy_from_slice(&value.unsigned_abs().to_le_bytes());
+    bytes
+}
+
+/// Changes the C-Type of an already bound binary column to `SQL_C_NUMERIC` with `precision` and
+/// `scale`. Setting any field in the descriptor record unbinds the data pointer, so we remember it
+/// and bind it again last.
+///
+/// # Safety
+///
+/// `statement` must be a valid statement handle. The column must have been bound to a binary buffer
+/// with elements of [`NUMERIC_STRUCT_SIZE`].
+pub unsafe fn bind_column_as_numeric(
+    statement: HStmt,
+    column_number: u16,
+    precision: u8,
+    scale: i8,
+) -> Result<(), Error> {
+    let record = column_number as i16;
+    let mut ard = descriptor(statement, StatementAttribute::AppRowDesc)?;
+    let data_ptr = pointer_field(&ard, record, Desc::DataPtr)?;
+    ard.set_type(record, CDataType::Numeric).into_result(&ard)?;
+    set_precision_and_scale(&mut ard, record, precision, scale)?;
+    ard.set_data_ptr(record, data_ptr).into_result(&ard)?;
+    Ok(())
+}
+
+/// Binds the buffer of an already bound binary parameter again, as `SQL_C_NUMERIC` with
+/// `precision` and `scale`. The parameter is described as `NUMERIC` to the data source.
+///
+/// # Safety
+///
+/// `statement` must be a valid statement handle. The parameter must have been bound to a binary
+/// buffer with elements of [`NUMERIC_STRUCT_SIZE`].
+pub unsafe fn bind_parameter_as_numeric(
+    statement: HStmt,
+    parameter_number: u16,
+    precision: u8,
+    scale: i8,
+) -> Result<(), Error> {
+    let record = parameter_number as i16;
+    let mut apd = descriptor(statement, StatementAttribute::AppParamDesc)?;
+    let data_ptr = pointer_field(&apd, record, Desc::DataPtr)?;
+    let indicator_ptr = pointer_field(&apd, record, Desc::IndicatorPtr)?;
+    let ret = SQLBindParameter(
+        statement,
+        parameter_number,
+        ParamType::Input,
+        CDataType::Numeric,
+        SqlDataType::NUMERIC,
+        precision.into(),
+        scale.into(),
+        data_ptr,
+        NUMERIC_STRUCT_SIZE as Len,
+        indicator_ptr as *mut Len,
+    );
+    into_result(ret, "SQLBindParameter", &RawStatement(statement))?;
+    // Binding the parameter only sets precision and scale of the implementation descriptor.
+    set_precision_and_scale(&mut apd, record, precision, scale)?;
+    apd.set_data_ptr(record, data_ptr).into_result(&apd)?;
+    Ok(())
+}
+
+fn set_precision_and_scale(
+    descriptor: &mut Descriptor<'_>,
+    record: i16,
+    precision: u8,
+    scale: i8,
+) -> Result<(), Error> {
+    descriptor
+        .set_precision(record, precision.into())
+        .into_result(descriptor)?;
+    descriptor
+        .set_scale(record, scale.into())
+        .into_result(descriptor)
+}
+
+/// Implicitly allocated descriptor of `statement`, e.g. the application row descriptor.
+pub unsafe fn descriptor(
+    statement: HStmt,
+    attribute: StatementAttribute,
+) -> Result<Descriptor<'static>, Error> {
+    let mut hdesc: HDesc = null_mut();
+    let ret = SQLGetStmtAttr(
+        statement,
+        attribute,
+        &mut hdesc as *mut HDesc as Pointer,
+        0,
+        null_mut(),
+    );
+    into_result(ret, "SQLGetStmtAttr", &RawStatement(statement))?;
+    Ok(Descriptor::new(hdesc))
+}
+
+/// Value of a pointer valued field of a descriptor record, e.g. `SQL_DESC_DATA_PTR`.
+pub unsafe fn pointer_field(
+    descriptor: &Descriptor<'_>,
+    record: i16,
+    field: Desc,
+) -> Result<Pointer, Error> {
+    let mut value: Pointer = null_mut();
+    let ret = SQLGetDescFieldW(
+        descriptor.as_sys(),
+        record,
+        field,
+        &mut value as *mut Pointer as Pointer,
+        IS_POINTER,
+        null_mut(),
+    );
+    into_result(ret, "SQLGetDescField", descriptor)?;
+    Ok(value)
+}
+
+pub fn into_result(
+    ret: SqlReturn,
+    function: &'static str,
+    handle: &impl AsHandle,
+) -> Result<(), Error> {
+    let result = match ret {
+        SqlReturn::SUCCESS => SqlResult::Success(()),
+        SqlReturn::SUCCESS_WITH_INFO => SqlResult::SuccessWithInfo(()),
+        _ => SqlResult::Error { function },
+    };
+    result.into_result(handle)
+}
+
+/// Allows us to fetch diagnostics for a statement we only know the raw handle of.
+pub struct RawStatement(pub HStmt);
+
+unsafe impl AsHandle for RawStatement {
+    fn as_handle(&self) -> Handle {
+        self.0 as Handle
+    }
+
+    fn handle_type(&self) -> HandleType {
+        HandleType::Stmt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        i128_to_numeric_struct, numeric_struct_to_i128, numeric_struct_to_string, MANTISSA_START,
+    };
+
+    #[test]
+    fn round_trip() {
+        for value in [0, 1, -1, 12345, -12345, i128::MAX, -i128::MAX] {
+            let bytes = i128_to_numeric_struct(value, 38, 2);
+
+            assert_eq!(Some(value), numeric_struct_to_i128(&bytes, 2));
+        }
+    }
+
+    #[test]
+    fn rescale_up() {
+        // 1.23 with scale 2 is 1.23000 with scale 5
+        let bytes = i128_to_numeric_struct(123, 10, 2);
+
+        assert_eq!(Some(123_000), numeric_struct_to_i128(&bytes, 5));
+    }
+
+    #[test]
+    fn rescale_up_overflows() {
+        let bytes = i128_to_numeric_struct(i128::MAX / 10, 38, 0);
+
+        assert_eq!(None, numeric_struct_to_i128(&bytes, 2));
+    }
+
+    #[test]
+    fn rescale_down_without_losing_digits() {
+        // 1.2300 with scale 4 is 1.23 with scale 2
+        let bytes = i128_to_numeric_struct(-12300, 10, 4);
+
+        assert_eq!(Some(-123), numeric_struct_to_i128(&bytes, 2));
+    }
+
+    #[test]
+    fn rescale_down_would_lose_digits() {
+        // 1.2345 can not be represented with a scale of 2
+        let bytes = i128_to_numeric_struct(12345, 10, 4);
+
+        assert_eq!(None, numeric_struct_to_i128(&bytes, 2));
+    }
+
+    #[test]
+    fn mantissa_exceeding_i128() {
+        let mut bytes = i128_to_numeric_struct(0, 38, 0);
+        bytes[MANTISSA_START..].copy_from_slice(&(1u128 << 127).to_le_bytes());
+
+        assert_eq!(None, numeric_struct_to_i128(&bytes, 0));
+    }
+
+    #[test]
+    fn render_as_text() {
+        assert_eq!(
+            "-123.45",
+            numeric_struct_to_string(&i128_to_numeric_struct(-12345, 10, 2))
+        );
+        assert_eq!(
+            "0.05",
+            numeric_struct_to_string(&i128_to_numeric_struct(5, 10, 2))
+        );
+        assert_eq!(
+            "1200",
+            numeric_struct_to_string(&i128_to_numeric_struct(12, 10, -2))
+        );
+    }
+
+    #[test]
+    fn render_mantissa_exceeding_i128_as_text() {
+        let mut bytes = i128_to_numeric_struct(0, 38, 0);
+        bytes[MANTISSA_START..].copy_from_slice(&(1u128 << 127).to_le_bytes());
+
+        assert_eq!(
+            "170141183460469231731687303715884105728",
+            numeric_struct_to_string(&bytes)
+        );
+    }
+}
@@ -5,17 +5,20 @@ use thiserror::Error;
 use arrow::{
     array::Array,
     datatypes::{
-        DataType, Date32Type, Date64Type, Field, Float16Type, Float32Type, Float64Type, Int16Type,
-        Int32Type, Int64Type, Int8Type, Schema, Time32MillisecondType, Time32SecondType,
+        DataType, Date32Type, Date64Type, DurationMicrosecondType, DurationMillisecondType,
+        DurationNanosecondType, DurationSecondType, Field, Float16Type, Float32Type, Float64Type,
+        Int16Type, Int32Type, Int64Type, Int8Type, IntervalDayTimeType, IntervalMonthDayNanoType,
+        IntervalUnit, IntervalYearMonthType, Schema, Time32MillisecondType, Time32SecondType,
         Time64MicrosecondType, Time64NanosecondType, TimeUnit, TimestampMicrosecondType,
-        TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt8Type,
+        TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt16Type,
+        UInt32Type, UInt64Type, UInt8Type,
     },
     error::ArrowError,
     record_batch::{RecordBatch, RecordBatchReader},
 };
 use odbc_api::{
     buffers::{AnyBuffer, AnySliceMut, BufferDesc},
-    handles::{AsStatementRef, StatementImpl},
+    handles::{AsStatementRef, Statement, StatementImpl},
     ColumnarBulkInserter, Connection, Prepared, StatementConnection,
 };
 
@@ -23,21 +26,41 @@ use crate::{
     date_time::{
         epoch_to_date, epoch_to_timestamp, sec_since_midnight_to_time, NullableTimeAsText,
     },
-    decimal::{NullableDecimal128AsText, NullableDecimal256AsText},
+    decimal::{
+        NullableDecimal128AsNumericStruct, NullableDecimal128AsText, NullableDecimal256AsText,
+    },
+    interval::{
+        bind_parameter_as_interval, convert_duration, IntervalKind, NullableIntervalAsStruct,
+        NullableIntervalAsText,
+    },
+    numeric::bind_parameter_as_numeric,
 };
 
+#[cfg(feature = "json")]
+use self::json::NestedAsJson;
+
 use self::{
-    binary::VariadicBinary,
+    binary::{FixedSizeBinary, VariadicBinary},
     boolean::boolean_to_bit,
+    flatten::flatten_arrays,
     map_arrow_to_odbc::MapArrowToOdbc,
-    text::{LargeUtf8ToNativeText, Utf8ToNativeText},
+    text::{LargeUtf8ToNativeText, NullableUInt64AsText, Utf8ToNativeText},
 };
 
 mod binary;
 mod boolean;
+mod create_table;
+mod flatten;
+#[cfg(feature = "json")]
+mod json;
 mod map_arrow_to_odbc;
 mod text;
 
+pub use self::{
+    create_table::{create_table_statement_from_schema, SqlDialect},
+    flatten::flatten_schema,
+};
+
 /// Fastest and most convinient way to stream the contents of arrow record batches into a database
 /// table. For usecase there you want to insert repeatedly into the same table from different
 /// streams it is more efficient to create an instance of [`self::OdbcWriter`] and reuse it.
@@ -142,8 +165,29 @@ pub enum WriterError {
     RebindBuffer(#[source] odbc_api::Error),
     #[error("The arrow data type {0} is not supported for insertion.")]
     UnsupportedArrowDataType(DataType),
+    #[error("Failed to render a nested array as JSON text.\n{0}")]
+    RenderJson(#[source] ArrowError),
     #[error("An error occured extracting a record batch from an error reader.\n{0}")]
     ReadingRecordBatch(#[source] ArrowError),
+    #[error(
+        "The value {value} of an Arrow {arrow_type} array is out of range for the ODBC {odbc_type} \
+        parameter it is bound to."
+    )]
+    ValueOutOfRange {
+        value: String,
+        arrow_type: DataType,
+        odbc_type: &'static str,
+    },
+    #[error(
+        "The arrow data type {data_type} requires a decimal column with a precision of \
+        {precision}, but {dialect:?} supports at most {max_precision} digits."
+    )]
+    DecimalPrecisionTooLarge {
+        data_type: DataType,
+        dialect: SqlDialect,
+        precision: i16,
+        max_precision: i16,
+    },
     #[error("An error occurred preparing SQL statement. SQL:\n{sql}\n{source}")]
     PreparingInsertStatement {
         #[source]
@@ -162,6 +206,9 @@ pub struct OdbcWriter<S> {
     /// to the database, and need to remember how to copy the data from an arrow array to an odbc
     /// mutable buffer slice for any column.
     strategies: Vec<Box<dyn WriteStrategy>>,
+    /// `true` if struct arrays are expanded into one parameter for each of their children. See
+    /// [`WriterOptions::flatten_structs`].
+    flatten_structs: bool,
 }
 
 impl<S> OdbcWriter<S>
@@ -193,19 +240,58 @@ where
         schema: &Schema,
         statement: Prepared<S>,
     ) -> Result<Self, WriterError> {
+        Self::with_options(row_capacity, schema, statement, &WriterOptions::default())
+    }
+
+    /// Like [`Self::new`], but allows to customize how arrays are bound as parameters.
+    pub fn with_options(
+        row_capacity: usize,
+        schema: &Schema,
+        mut statement: Prepared<S>,
+        options: &WriterOptions,
+    ) -> Result<Self, WriterError> {
+        let flattened;
+        let schema = if let Some(separator) = &options.flatten_structs {
+            flattened = flatten_schema(schema, separator);
+            &flattened
+        } else {
+            schema
+        };
         let strategies: Vec<_> = schema
             .fields()
             .iter()
-            .map(|field| field_to_write_strategy(field.as_ref()))
+            .map(|field| field_to_write_strategy(field.as_ref(), options))
             .collect::<Result<_, _>>()?;
         let descriptions = strategies.iter().map(|cws| cws.buffer_desc());
+        // The inserter takes ownership of the statement. Remember the handle, so we can alter the
+        // bindings it creates.
+        let statement_handle = statement.as_stmt_ref().as_sys();
         let inserter = statement
             .into_column_inserter(row_capacity, descriptions)
             .map_err(WriterError::BindParameterBuffers)?;
+        for (index, strategy) in strategies.iter().enumerate() {
+            if let Some((precision, scale)) = strategy.numeric_precision_and_scale() {
+                let parameter_number = (index + 1).try_into().unwrap();
+                // Safety: The inserter owns the statement, so the handle is valid. The strategy
+                // described a binary buffer with elements of the size of a numeric struct.
+                unsafe {
+                    bind_parameter_as_numeric(statement_handle, parameter_number, precision, scale)
+                }
+                .map_err(WriterError::BindParameterBuffers)?;
+            }
+            if let Some(kind) = strategy.interval_kind() {
+                let parameter_number = (index + 1).try_into().unwrap();
+                // Safety: The inserter owns the statement, so the handle is valid. The strategy
+                // described a binary buffer with elements of the size of an interval struct.
+                unsafe { bind_parameter_as_interval(statement_handle, parameter_number, kind) }
+                    .map_err(WriterError::BindParameterBuffers)?;
+            }
+        }
 
         Ok(Self {
             inserter,
             strategies,
+            flatten_structs: options.flatten_structs.is_some(),
         })
     }
 
@@ -236,11 +322,13 @@ where
             let param_offset = self.inserter.num_rows();
             self.inserter.set_num_rows(param_offset + chunk_size);
             let chunk = record_batch.slice(record_batch.num_rows() - remanining_rows, chunk_size);
-            for (index, (array, strategy)) in chunk
-                .columns()
-                .iter()
-                .zip(self.strategies.iter())
-                .enumerate()
+            let mut arrays = Vec::new();
+            if self.flatten_structs {
+                flatten_arrays(chunk.columns(), &mut arrays);
+            } else {
+                arrays.extend_from_slice(chunk.columns());
+            }
+            for (index, (array, strategy)) in arrays.iter().zip(self.strategies.iter()).enumerate()
             {
                 strategy.write_rows(param_offset, self.inserter.column_mut(index), array)?
             }
@@ -332,9 +420,97 @@ pub trait WriteStrategy {
         column_buf: AnySliceMut<'_>,
         array: &dyn Array,
     ) -> Result<(), WriterError>;
+
+    /// Precision and scale, if the parameter is to be bound as `SQL_NUMERIC_STRUCT`. In this case
+    /// [`Self::buffer_desc`] must describe a binary buffer with elements of the size of a numeric
+    /// struct.
+    fn numeric_precision_and_scale(&self) -> Option<(u8, i8)> {
+        None
+    }
+
+    /// Kind of interval, if the parameter is to be bound as `SQL_INTERVAL_STRUCT`. In this case
+    /// [`Self::buffer_desc`] must describe a binary buffer with elements of the size of an interval
+    /// struct.
+    fn interval_kind(&self) -> Option<IntervalKind> {
+        None
+    }
 }
 
-fn field_to_write_strategy(field: &Field) -> Result<Box<dyn WriteStrategy>, WriterError> {
+/// Options controlling how [`OdbcWriter`] binds arrays as parameters. See
+/// [`OdbcWriter::with_options`]. New options may be added in minor releases, so construct it with
+/// [`WriterOptions::default`] and assign the fields you want to change.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct WriterOptions {
+    /// Bind `Decimal128` arrays as `SQL_NUMERIC_STRUCT` with explicit precision and scale, rather
+    /// than formatting each value as text which is parsed again by the driver. Only applies to
+    /// decimals with non negative scale. `false` by default.
+    pub decimals_as_numeric_struct: bool,
+    /// Bind `UInt64` arrays as decimal text, e.g. for `DECIMAL(20,0)` or `VARCHAR` columns. By
+    /// default they are bound as `BIGINT`, in which case values beyond `i64::MAX` are rejected with
+    /// [`WriterError::ValueOutOfRange`]. `false` by default.
+    pub uint64_as_text: bool,
+    /// How to bind `Duration` arrays. By default durations are bound as `BIGINT` counting in the
+    /// unit of the Arrow type.
+    pub duration_format: DurationFormat,
+    /// How to bind `Interval` arrays. ISO 8601 text by default.
+    pub interval_format: IntervalFormat,
+    /// Names of columns whose `List`, `LargeList`, `Struct` or `Map` arrays are rendered as JSON
+    /// text, e.g. to insert them into JSON or `NVARCHAR(MAX)` columns. Each element becomes one
+    /// JSON document, rendered by `arrow-json`. Temporal values are written as JSON strings, non
+    /// finite floats as `null`. Arrays containing types `arrow-json` can not render, e.g. decimals,
+    /// binary or maps with non string keys, are rejected with
+    /// [`WriterError::UnsupportedArrowDataType`]. Requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub json_columns: Vec<String>,
+    /// Render nested arrays of any column as JSON text, if no other representation applies to
+    /// them. `false` by default, in which case nested arrays outside of [`Self::json_columns`] are
+    /// rejected with [`WriterError::UnsupportedArrowDataType`]. Requires the `json` feature.
+    #[cfg(feature = "json")]
+    pub nested_as_json: bool,
+    /// Expand `Struct` arrays into one parameter for each of their children, rather than binding
+    /// them as a single parameter. The value is the separator between the names of parent and
+    /// child, e.g. `Some("_".to_string())` maps `address.city` to `address_city`. Takes precedence
+    /// over rendering struct columns as JSON text. The statement must have one placeholder for each
+    /// field of [`crate::flatten_schema`]. `None` by default.
+    pub flatten_structs: Option<String>,
+}
+
+/// Representation of Arrow `Duration` arrays, see [`WriterOptions::duration_format`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DurationFormat {
+    /// `BIGINT` counting in `unit`. `None` keeps the unit of the Arrow type. Converting to a coarser
+    /// unit truncates towards zero, converting to a finer unit fails with
+    /// [`WriterError::ValueOutOfRange`] if the result exceeds 64 Bit.
+    BigInt { unit: Option<TimeUnit> },
+    /// Text formatted as SQL `DAY TO SECOND` interval literal, e.g. `-1 02:03:04.500`. The number
+    /// of fractional digits is determined by the unit of the Arrow type.
+    IntervalText,
+}
+
+impl Default for DurationFormat {
+    fn default() -> Self {
+        DurationFormat::BigInt { unit: None }
+    }
+}
+
+/// Representation of Arrow `Interval` arrays, see [`WriterOptions::interval_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntervalFormat {
+    /// Text formatted as ISO 8601 duration, e.g. `P1Y2M` or `P3DT4H5M6.7S`.
+    #[default]
+    Iso8601,
+    /// `SQL_INTERVAL_STRUCT`. `YearMonth` intervals are bound as `SQL_INTERVAL_YEAR_TO_MONTH`,
+    /// `DayTime` and `MonthDayNano` intervals as `SQL_INTERVAL_DAY_TO_SECOND`. Month day nano
+    /// intervals with a non zero number of months can not be expressed in days and fail with
+    /// [`WriterError::ValueOutOfRange`].
+    SqlInterval,
+}
+
+fn field_to_write_strategy(
+    field: &Field,
+    options: &WriterOptions,
+) -> Result<Box<dyn WriteStrategy>, WriterError> {
     let is_nullable = field.is_nullable();
     let strategy = match field.data_type() {
         DataType::Utf8 => Box::new(Utf8ToNativeText {}),
@@ -345,6 +521,20 @@ fn field_to_write_strategy(field: &Field) -> Result<Box<dyn WriteStrategy>, Writ
         DataType::Int32 => Int32Type::identical(is_nullable),
         DataType::Int64 => Int64Type::identical(is_nullable),
         DataType::UInt8 => UInt8Type::identical(is_nullable),
+        // Unsigned integers are bound to the next wider signed type, so they can be inserted into
+        // the signed integer columns supported by most databases.
+        DataType::UInt16 => UInt16Type::map_with(is_nullable, i32::from),
+        DataType::UInt32 => UInt32Type::map_with(is_nullable, i64::from),
+        DataType::UInt64 if options.uint64_as_text => Box::new(NullableUInt64AsText),
+        // There is no wider signed type for 64 Bit integers, so values beyond `i64::MAX` are
+        // rejected.
+        DataType::UInt64 => UInt64Type::try_map_with(is_nullable, |value| {
+            i64::try_from(value).map_err(|_| WriterError::ValueOutOfRange {
+                value: value.to_string(),
+                arrow_type: DataType::UInt64,
+                odbc_type: "BIGINT",
+            })
+        }),
         DataType::Float16 => Float16Type::map_with(is_nullable, |half| half.to_f32()),
         DataType::Float32 => Float32Type::identical(is_nullable),
         DataType::Float64 => Float64Type::identical(is_nullable),
@@ -379,9 +569,13 @@ fn field_to_write_strategy(field: &Field) -> Result<Box<dyn WriteStrategy>, Writ
         DataType::Time64(TimeUnit::Nanosecond) => {
             Box::new(NullableTimeAsText::<Time64NanosecondType>::new())
         }
-        DataType::Binary => Box::new(VariadicBinary::new(1)),
+        DataType::Binary => Box::new(VariadicBinary::<i32>::new(1)),
+        DataType::LargeBinary => Box::new(VariadicBinary::<i64>::new(1)),
         DataType::FixedSizeBinary(length) => {
-            Box::new(VariadicBinary::new((*length).try_into().unwrap()))
+            Box::new(FixedSizeBinary::new((*length).try_into().unwrap()))
+        }
+        DataType::Decimal128(precision, scale @ 0..) if options.decimals_as_numeric_struct => {
+            Box::new(NullableDecimal128AsNumericStruct::new(*precision, *scale))
         }
         DataType::Decimal128(precision, scale) => {
             Box::new(NullableDecimal128AsText::new(*precision, *scale))
@@ -389,7 +583,79 @@ fn field_to_write_strategy(field: &Field) -> Result<Box<dyn WriteStrategy>, Writ
         DataType::Decimal256(precision, scale) => {
             Box::new(NullableDecimal256AsText::new(*precision, *scale))
         }
+        DataType::Duration(unit) => {
+            duration_to_write_strategy(unit, is_nullable, &options.duration_format)
+        }
+        DataType::Interval(unit) => interval_to_write_strategy(unit, options.interval_format),
+        #[cfg(feature = "json")]
+        DataType::List(_) | DataType::LargeList(_) | DataType::Struct(_) | DataType::Map(_, _)
+            if options.nested_as_json || options.json_columns.contains(field.name()) =>
+        {
+            Box::new(NestedAsJson::new(field.data_type())?)
+        }
         unsupported => return Err(WriterError::UnsupportedArrowDataType(unsupported.clone())),
     };
     Ok(strategy)
 }
+
+fn duration_to_write_strategy(
+    unit: &TimeUnit,
+    is_nullable: bool,
+    format: &DurationFormat,
+) -> Box<dyn WriteStrategy> {
+    match format {
+        DurationFormat::BigInt { unit: target } => {
+            let target = target.as_ref().unwrap_or(unit).clone();
+            let source = unit.clone();
+            let convert = move |value| convert_duration(value, &source, &target);
+            match unit {
+                TimeUnit::Second => DurationSecondType::try_map_with(is_nullable, convert),
+                TimeUnit::Millisecond => {
+                    DurationMillisecondType::try_map_with(is_nullable, convert)
+                }
+                TimeUnit::Microsecond => {
+                    DurationMicrosecondType::try_map_with(is_nullable, convert)
+                }
+                TimeUnit::Nanosecond => DurationNanosecondType::try_map_with(is_nullable, convert),
+            }
+        }
+        DurationFormat::IntervalText => match unit {
+            TimeUnit::Second => Box::new(NullableIntervalAsText::<DurationSecondType>::new()),
+            TimeUnit::Millisecond => {
+                Box::new(NullableIntervalAsText::<DurationMillisecondType>::new())
+            }
+            TimeUnit::Microsecond => {
+                Box::new(NullableIntervalAsText::<DurationMicrosecondType>::new())
+            }
+            TimeUnit::Nanosecond => {
+                Box::new(NullableIntervalAsText::<DurationNanosecondType>::new())
+            }
+        },
+    }
+}
+
+fn interval_to_write_strategy(
+    unit: &IntervalUnit,
+    format: IntervalFormat,
+) -> Box<dyn WriteStrategy> {
+    match (format, unit) {
+        (IntervalFormat::Iso8601, IntervalUnit::YearMonth) => {
+            Box::new(NullableIntervalAsText::<IntervalYearMonthType>::new())
+        }
+        (IntervalFormat::Iso8601, IntervalUnit::DayTime) => {
+            Box::new(NullableIntervalAsText::<IntervalDayTimeType>::new())
+        }
+        (IntervalFormat::Iso8601, IntervalUnit::MonthDayNano) => {
+            Box::new(NullableIntervalAsText::<IntervalMonthDayNanoType>::new())
+        }
+        (IntervalFormat::SqlInterval, IntervalUnit::YearMonth) => {
+            Box::new(NullableIntervalAsStruct::<IntervalYearMonthType>::new())
+        }
+        (IntervalFormat::SqlInterval, IntervalUnit::DayTime) => {
+            Box::new(NullableIntervalAsStruct::<IntervalDayTimeType>::new())
+        }
+        (IntervalFormat::SqlInterval, IntervalUnit::MonthDayNano) => {
+            Box::new(NullableIntervalAsStruct::<IntervalMonthDayNanoType>::new())
+        }
+    }
+}
@@ -1,19 +1,30 @@
-use arrow::array::{Array, BinaryArray};
+use std::marker::PhantomData;
+
+use arrow::array::{Array, FixedSizeBinaryArray, GenericBinaryArray, OffsetSizeTrait};
 use odbc_api::buffers::{AnySliceMut, BufferDesc};
 
 use super::{WriteStrategy, WriterError};
 
-pub struct VariadicBinary {
+/// Writes `Binary` (`O` is `i32`) or `LargeBinary` (`O` is `i64`) arrays. The parameter buffer
+/// grows with the largest element written so far.
+pub struct VariadicBinary<O> {
     buffer_start_size: usize,
+    _offset_size: PhantomData<O>,
 }
 
-impl VariadicBinary {
+impl<O> VariadicBinary<O> {
     pub fn new(buffer_start_size: usize) -> Self {
-        VariadicBinary { buffer_start_size }
+        VariadicBinary {
+            buffer_start_size,
+            _offset_size: PhantomData,
+        }
     }
 }
 
-impl WriteStrategy for VariadicBinary {
+impl<O> WriteStrategy for VariadicBinary<O>
+where
+    O: OffsetSizeTrait,
+{
     fn buffer_desc(&self) -> BufferDesc {
         BufferDesc::Binary {
             length: self.buffer_start_size,
@@ -26,7 +37,10 @@ impl WriteStrategy for VariadicBinary {
         to: AnySliceMut<'_>,
         from: &dyn Array,
     ) -> Result<(), WriterError> {
-        let from = from.as_any().downcast_ref::<BinaryArray>().unwrap();
+        let from = from
+            .as_any()
+            .downcast_ref::<GenericBinaryArray<O>>()
+            .unwrap();
         let mut to = to.as_bin_view().unwrap();
         for (row_index, element) in from.iter().enumerate() {
             if let Some(bytes) = element {
@@ -40,3 +54,40 @@ impl WriteStrategy for VariadicBinary {
         Ok(())
     }
 }
+
+/// Writes `FixedSizeBinary` arrays. Every element has the same length, so the parameter buffer is
+/// never rebound.
+pub struct FixedSizeBinary {
+    length: usize,
+}
+
+impl FixedSizeBinary {
+    pub fn new(length: usize) -> Self {
+        FixedSizeBinary { length }
+    }
+}
+
+impl WriteStrategy for FixedSizeBinary {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Binary {
+            length: self.length,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        to: AnySliceMut<'_>,
+        from: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = from
+            .as_any()
+            .downcast_ref::<FixedSizeBinaryArray>()
+            .unwrap();
+        let mut to = to.as_bin_view().unwrap();
+        for (row_index, element) in from.iter().enumerate() {
+            to.set_cell(param_offset + row_index, element);
+        }
+        Ok(())
+    }
+}
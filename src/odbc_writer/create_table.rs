@@ -0,0 +1,428 @@
+use std::borrow::Cow;
+
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+
+use super::{valid_in_column_name, WriterError};
+
+/// Database flavours [`create_table_statement_from_schema`] can generate column types for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    /// Microsoft SQL Server
+    MsSql,
+    PostgreSql,
+    MySql,
+    Sqlite,
+    Oracle,
+    /// IBM Db2
+    Db2,
+    /// Types as named by the SQL standard, for any other database.
+    Ansi,
+}
+
+impl SqlDialect {
+    /// Quotes `column_name` if it contains characters which are not valid in an unquoted
+    /// identifier. MySQL uses backticks, everyone else double quotes.
+    fn quote<'a>(&self, column_name: &'a str) -> Cow<'a, str> {
+        if !column_name.contains(|c| !valid_in_column_name(c)) {
+            Cow::Borrowed(column_name)
+        } else if *self == SqlDialect::MySql {
+            Cow::Owned(format!("`{column_name}`"))
+        } else {
+            Cow::Owned(format!("\"{column_name}\""))
+        }
+    }
+
+    /// Column type able to hold the values [`crate::OdbcWriter`] binds for arrays of `data_type`,
+    /// using the default [`crate::WriterOptions`].
+    fn column_type(&self, data_type: &DataType) -> Result<String, WriterError> {
+        use SqlDialect::*;
+        let column_type = match data_type {
+            DataType::Boolean => match self {
+                MsSql => "BIT",
+                Sqlite => "INTEGER",
+                Oracle => "NUMBER(1)",
+                PostgreSql | MySql | Db2 | Ansi => "BOOLEAN",
+            }
+            .to_owned(),
+            DataType::Int8 => match self {
+                // `TINYINT` is unsigned in MSSQL
+                MySql => "TINYINT",
+                Sqlite => "INTEGER",
+                Oracle => "NUMBER(3)",
+                MsSql | PostgreSql | Db2 | Ansi => "SMALLINT",
+            }
+            .to_owned(),
+            DataType::UInt8 => match self {
+                MsSql => "TINYINT",
+                MySql => "TINYINT UNSIGNED",
+                Sqlite => "INTEGER",
+                Oracle => "NUMBER(3)",
+                PostgreSql | Db2 | Ansi => "SMALLINT",
+            }
+            .to_owned(),
+            DataType::Int16 => match self {
+                Sqlite => "INTEGER",
+                Oracle => "NUMBER(5)",
+                MsSql | PostgreSql | MySql | Db2 | Ansi => "SMALLINT",
+            }
+            .to_owned(),
+            // Unsigned integers are bound as the next wider signed integer
+            DataType::Int32 | DataType::UInt16 => match self {
+                MsSql => "INT",
+                Oracle => "NUMBER(10)",
+                PostgreSql | MySql | Sqlite | Db2 | Ansi => "INTEGER",
+            }
+            .to_owned(),
+            // Durations are bound as `BIGINT` by default
+            DataType::Int64 | DataType::UInt32 | DataType::UInt64 | DataType::Duration(_) => {
+                match self {
+                    Sqlite => "INTEGER",
+                    Oracle => "NUMBER(19)",
+                    MsSql | PostgreSql | MySql | Db2 | Ansi => "BIGINT",
+                }
+                .to_owned()
+            }
+            DataType::Float16 | DataType::Float32 => match self {
+                MySql => "FLOAT",
+                Oracle => "BINARY_FLOAT",
+                MsSql | PostgreSql | Sqlite | Db2 | Ansi => "REAL",
+            }
+            .to_owned(),
+            DataType::Float64 => match self {
+                MsSql => "FLOAT",
+                MySql | Db2 => "DOUBLE",
+                Sqlite => "REAL",
+                Oracle => "BINARY_DOUBLE",
+                PostgreSql | Ansi => "DOUBLE PRECISION",
+            }
+            .to_owned(),
+            DataType::Utf8 | DataType::LargeUtf8 => match self {
+                MsSql => "NVARCHAR(MAX)",
+                PostgreSql | Sqlite => "TEXT",
+                MySql => "LONGTEXT",
+                Oracle | Db2 | Ansi => "CLOB",
+            }
+            .to_owned(),
+            DataType::Binary | DataType::LargeBinary => match self {
+                MsSql => "VARBINARY(MAX)",
+                PostgreSql => "BYTEA",
+                MySql => "LONGBLOB",
+                Sqlite | Oracle | Db2 | Ansi => "BLOB",
+            }
+            .to_owned(),
+            DataType::FixedSizeBinary(length) => match self {
+                PostgreSql => "BYTEA".to_owned(),
+                Sqlite => "BLOB".to_owned(),
+                Oracle => format!("RAW({length})"),
+                MsSql | MySql | Db2 | Ansi => format!("BINARY({length})"),
+            },
+            DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale) => {
+                // Only Oracle supports negative scales. Everyone else gets an integer wide enough to
+                // hold the trailing zeros.
+                let (precision, scale) = match (self, *scale) {
+                    (Oracle, scale) | (_, scale @ 0..) => (i16::from(*precision), scale),
+                    (_, scale) => (i16::from(*precision) - i16::from(scale), 0),
+                };
+                if let Some(max_precision) = self.max_decimal_precision() {
+                    if precision > max_precision {
+                        return Err(WriterError::DecimalPrecisionTooLarge {
+                            data_type: data_type.clone(),
+                            dialect: *self,
+                            precision,
+                            max_precision,
+                        });
+                    }
+                }
+                match self {
+                    Sqlite => "NUMERIC".to_owned(),
+                    Oracle => format!("NUMBER({precision},{scale})"),
+                    PostgreSql => format!("NUMERIC({precision},{scale})"),
+                    MsSql | MySql | Db2 | Ansi => format!("DECIMAL({precision},{scale})"),
+                }
+            }
+            DataType::Date32 | DataType::Date64 => match self {
+                Sqlite => "TEXT",
+                MsSql | PostgreSql | MySql | Oracle | Db2 | Ansi => "DATE",
+            }
+            .to_owned(),
+            DataType::Timestamp(unit, None) => {
+                let precision = fractional_digits(unit);
+                match self {
+                    MsSql => format!("DATETIME2({})", precision.min(7)),
+                    PostgreSql => format!("TIMESTAMP({})", precision.min(6)),
+                    MySql => format!("DATETIME({})", precision.min(6)),
+                    Sqlite => "TEXT".to_owned(),
+                    Oracle | Db2 | Ansi => format!("TIMESTAMP({precision})"),
+                }
+            }
+            DataType::Time32(unit @ (TimeUnit::Second | TimeUnit::Millisecond))
+            | DataType::Time64(unit @ (TimeUnit::Microsecond | TimeUnit::Nanosecond)) => {
+                let precision = fractional_digits(unit);
+                match self {
+                    MsSql => format!("TIME({})", precision.min(7)),
+                    PostgreSql | MySql => format!("TIME({})", precision.min(6)),
+                    Sqlite => "TEXT".to_owned(),
+                    // Oracle has no time of day type. Times with fractional seconds are bound as
+                    // text, whole seconds as ODBC times, which the driver converts to text.
+                    Oracle => "VARCHAR2(18)".to_owned(),
+                    // Db2 does not store fractional seconds for times
+                    Db2 => "TIME".to_owned(),
+                    Ansi => format!("TIME({precision})"),
+                }
+            }
+            // Intervals are bound as ISO 8601 text by default
+            DataType::Interval(_) => match self {
+                Sqlite => "TEXT",
+                Oracle => "VARCHAR2(64)",
+                MsSql | PostgreSql | MySql | Db2 | Ansi => "VARCHAR(64)",
+            }
+            .to_owned(),
+            unsupported => return Err(WriterError::UnsupportedArrowDataType(unsupported.clone())),
+        };
+        Ok(column_type)
+    }
+
+    /// Largest precision of a decimal column, `None` if we do not know of any limit. SQLite stores
+    /// decimals without a precision.
+    fn max_decimal_precision(&self) -> Option<i16> {
+        match self {
+            SqlDialect::MsSql | SqlDialect::Oracle => Some(38),
+            SqlDialect::Db2 => Some(31),
+            SqlDialect::MySql => Some(65),
+            SqlDialect::PostgreSql => Some(1000),
+            SqlDialect::Sqlite | SqlDialect::Ansi => None,
+        }
+    }
+
+    /// Definition of a single column within the `CREATE TABLE` statement.
+    fn column_definition(&self, field: &Field) -> Result<String, WriterError> {
+        let name = self.quote(field.name());
+        let column_type = self.column_type(field.data_type())?;
+        let constraint = match (field.is_nullable(), self) {
+            (false, _) => " NOT NULL",
+            // Whether columns are nullable by default depends on the session settings in MSSQL.
+            (true, SqlDialect::MsSql) => " NULL",
+            (true, _) => "",
+        };
+        Ok(format!("{name} {column_type}{constraint}"))
+    }
+}
+
+fn fractional_digits(unit: &TimeUnit) -> u8 {
+    match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::Millisecond => 3,
+        TimeUnit::Microsecond => 6,
+        // The writer binds timestamps with a precision of 7 digits, the database decides whether
+        // to keep more.
+        TimeUnit::Nanosecond => 9,
+    }
+}
+
+/// Creates an SQL statement creating a table, which can hold the record batches of `schema` as
+/// inserted by [`crate::OdbcWriter`] with default [`crate::WriterOptions`]. Column types are
+/// chosen for the `dialect` of the database. Non nullable fields become `NOT NULL` columns and
+/// decimals keep their precision and scale. Column names are quoted like in
+/// [`crate::insert_statement_from_schema`], for MySQL using backticks.
+///
+/// Fails with [`WriterError::UnsupportedArrowDataType`] for fields the writer can not insert by
+/// default, e.g. nested types or timestamps with time zone. Decimals with more digits than the
+/// `dialect` supports (38 for MSSQL and Oracle, 31 for Db2 and 65 for MySQL) fail with
+/// [`WriterError::DecimalPrecisionTooLarge`].
+///
+/// # Example
+///
+/// ```
+/// use arrow_odbc::{
+///     create_table_statement_from_schema, SqlDialect,
+///     arrow::datatypes::{Field, DataType, Schema},
+/// };
+///
+/// let field_a = Field::new("a", DataType::Int64, false);
+/// let field_b = Field::new("b", DataType::Decimal128(10, 2), true);
+///
+/// let schema = Schema::new(vec![field_a, field_b]);
+/// let sql = create_table_statement_from_schema(&schema, "MyTable", SqlDialect::PostgreSql)
+///     .unwrap();
+///
+/// assert_eq!("CREATE TABLE MyTable (a BIGINT NOT NULL, b NUMERIC(10,2))", sql)
+/// ```
+///
+/// **Note:**
+///
+/// If table or column names are derived from user input, be sure to sanatize the input in order to
+/// prevent SQL injection attacks.
+pub fn create_table_statement_from_schema(
+    schema: &Schema,
+    table_name: &str,
+    dialect: SqlDialect,
+) -> Result<String, WriterError> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| dialect.column_definition(field))
+        .collect::<Result<Vec<_>, _>>()?
+        .join(", ");
+    // No semicolon, for the same reason as in the insert statement.
+    Ok(format!("CREATE TABLE {table_name} ({columns})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{DataType, Field, IntervalUnit, Schema, TimeUnit};
+
+    use crate::WriterError;
+
+    use super::{create_table_statement_from_schema, SqlDialect};
+
+    /// One field for each family of types the writer can insert by default.
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new("a", DataType::Boolean, false),
+            Field::new("b", DataType::Int8, true),
+            Field::new("c", DataType::UInt8, true),
+            Field::new("d", DataType::Int16, true),
+            Field::new("e", DataType::Int32, true),
+            Field::new("f", DataType::Int64, true),
+            Field::new("g", DataType::Float32, true),
+            Field::new("h", DataType::Float64, true),
+            Field::new("i", DataType::Utf8, true),
+            Field::new("j", DataType::Binary, true),
+            Field::new("k", DataType::FixedSizeBinary(16), true),
+            Field::new("l", DataType::Decimal128(10, 2), true),
+            Field::new("m", DataType::Decimal128(5, -2), true),
+            Field::new("n", DataType::Date32, true),
+            Field::new("o", DataType::Timestamp(TimeUnit::Millisecond, None), true),
+            Field::new("p", DataType::Time64(TimeUnit::Nanosecond), true),
+            Field::new("q q", DataType::Interval(IntervalUnit::YearMonth), true),
+        ])
+    }
+
+    fn create_table(dialect: SqlDialect) -> String {
+        create_table_statement_from_schema(&schema(), "t", dialect).unwrap()
+    }
+
+    fn decimal_column(data_type: DataType, dialect: SqlDialect) -> Result<String, WriterError> {
+        let schema = Schema::new(vec![Field::new("a", data_type, true)]);
+        create_table_statement_from_schema(&schema, "t", dialect)
+    }
+
+    #[test]
+    fn mysql() {
+        assert_eq!(
+            "CREATE TABLE t (a BOOLEAN NOT NULL, b TINYINT, c TINYINT UNSIGNED, d SMALLINT, \
+            e INTEGER, f BIGINT, g FLOAT, h DOUBLE, i LONGTEXT, j LONGBLOB, k BINARY(16), \
+            l DECIMAL(10,2), m DECIMAL(7,0), n DATE, o DATETIME(3), p TIME(6), `q q` VARCHAR(64))",
+            create_table(SqlDialect::MySql)
+        );
+    }
+
+    #[test]
+    fn sqlite() {
+        assert_eq!(
+            "CREATE TABLE t (a INTEGER NOT NULL, b INTEGER, c INTEGER, d INTEGER, e INTEGER, \
+            f INTEGER, g REAL, h REAL, i TEXT, j BLOB, k BLOB, l NUMERIC, m NUMERIC, n TEXT, \
+            o TEXT, p TEXT, \"q q\" TEXT)",
+            create_table(SqlDialect::Sqlite)
+        );
+    }
+
+    #[test]
+    fn oracle() {
+        assert_eq!(
+            "CREATE TABLE t (a NUMBER(1) NOT NULL, b NUMBER(3), c NUMBER(3), d NUMBER(5), \
+            e NUMBER(10), f NUMBER(19), g BINARY_FLOAT, h BINARY_DOUBLE, i CLOB, j BLOB, \
+            k RAW(16), l NUMBER(10,2), m NUMBER(5,-2), n DATE, o TIMESTAMP(3), p VARCHAR2(18), \
+            \"q q\" VARCHAR2(64))",
+            create_table(SqlDialect::Oracle)
+        );
+    }
+
+    #[test]
+    fn db2() {
+        assert_eq!(
+            "CREATE TABLE t (a BOOLEAN NOT NULL, b SMALLINT, c SMALLINT, d SMALLINT, e INTEGER, \
+            f BIGINT, g REAL, h DOUBLE, i CLOB, j BLOB, k BINARY(16), l DECIMAL(10,2), \
+            m DECIMAL(7,0), n DATE, o TIMESTAMP(3), p TIME, \"q q\" VARCHAR(64))",
+            create_table(SqlDialect::Db2)
+        );
+    }
+
+    #[test]
+    fn ansi() {
+        assert_eq!(
+            "CREATE TABLE t (a BOOLEAN NOT NULL, b SMALLINT, c SMALLINT, d SMALLINT, e INTEGER, \
+            f BIGINT, g REAL, h DOUBLE PRECISION, i CLOB, j BLOB, k BINARY(16), l DECIMAL(10,2), \
+            m DECIMAL(7,0), n DATE, o TIMESTAMP(3), p TIME(9), \"q q\" VARCHAR(64))",
+            create_table(SqlDialect::Ansi)
+        );
+    }
+
+    #[test]
+    fn decimal_precision_within_dialect_maximum() {
+        let column = |data_type, dialect| decimal_column(data_type, dialect).unwrap();
+
+        assert_eq!(
+            "CREATE TABLE t (a DECIMAL(38,0) NULL)",
+            column(DataType::Decimal128(36, -2), SqlDialect::MsSql)
+        );
+        assert_eq!(
+            "CREATE TABLE t (a NUMBER(38,-10))",
+            column(DataType::Decimal128(38, -10), SqlDialect::Oracle)
+        );
+        assert_eq!(
+            "CREATE TABLE t (a DECIMAL(31,5))",
+            column(DataType::Decimal128(31, 5), SqlDialect::Db2)
+        );
+        assert_eq!(
+            "CREATE TABLE t (a DECIMAL(65,0))",
+            column(DataType::Decimal256(65, 0), SqlDialect::MySql)
+        );
+        assert_eq!(
+            "CREATE TABLE t (a NUMERIC(76,10))",
+            column(DataType::Decimal256(76, 10), SqlDialect::PostgreSql)
+        );
+        assert_eq!(
+            "CREATE TABLE t (a NUMERIC)",
+            column(DataType::Decimal256(76, 10), SqlDialect::Sqlite)
+        );
+        assert_eq!(
+            "CREATE TABLE t (a DECIMAL(76,10))",
+            column(DataType::Decimal256(76, 10), SqlDialect::Ansi)
+        );
+    }
+
+    #[test]
+    fn decimal_precision_exceeding_dialect_maximum() {
+        let max_precision = |data_type, dialect| match decimal_column(data_type, dialect) {
+            Err(WriterError::DecimalPrecisionTooLarge {
+                precision,
+                max_precision,
+                ..
+            }) => (precision, max_precision),
+            other => panic!("Expected precision to be too large, got {other:?}"),
+        };
+
+        assert_eq!(
+            (39, 38),
+            max_precision(DataType::Decimal256(39, 0), SqlDialect::MsSql)
+        );
+        // Negative scales add trailing zeros to the integer
+        assert_eq!(
+            (40, 38),
+            max_precision(DataType::Decimal128(38, -2), SqlDialect::MsSql)
+        );
+        assert_eq!(
+            (39, 38),
+            max_precision(DataType::Decimal256(39, -5), SqlDialect::Oracle)
+        );
+        assert_eq!(
+            (32, 31),
+            max_precision(DataType::Decimal128(32, 2), SqlDialect::Db2)
+        );
+        assert_eq!(
+            (66, 65),
+            max_precision(DataType::Decimal256(60, -6), SqlDialect::MySql)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{make_array, Array, ArrayRef, AsArray},
+    buffer::NullBuffer,
+    datatypes::{DataType, FieldRef, Schema},
+};
+
+/// Replaces every `Struct` field in `schema` with its children, named
+/// `<parent><separator><child>`. Nested structs are expanded recursively. Children of a nullable
+/// parent become nullable, since a null parent implies null children. Use this together with
+/// [`crate::insert_statement_from_schema`] to generate an insert statement for a writer with
+/// [`crate::WriterOptions::flatten_structs`] set.
+///
+/// # Example
+///
+/// ```
+/// use arrow_odbc::{
+///     flatten_schema, insert_statement_from_schema,
+///     arrow::datatypes::{Field, DataType, Schema},
+/// };
+///
+/// let street = Field::new("street", DataType::Utf8, false);
+/// let city = Field::new("city", DataType::Utf8, false);
+/// let address = Field::new("address", DataType::Struct(vec![street, city].into()), true);
+///
+/// let schema = flatten_schema(&Schema::new(vec![address]), "_");
+/// let sql = insert_statement_from_schema(&schema, "MyTable");
+///
+/// assert_eq!(
+///     "INSERT INTO MyTable (address_street, address_city) VALUES (?, ?)",
+///     sql
+/// );
+/// assert!(schema.field(0).is_nullable());
+/// ```
+pub fn flatten_schema(schema: &Schema, separator: &str) -> Schema {
+    let mut fields = Vec::new();
+    for field in schema.fields() {
+        flatten_field(field, separator, &mut fields);
+    }
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+fn flatten_field(field: &FieldRef, separator: &str, out: &mut Vec<FieldRef>) {
+    let DataType::Struct(children) = field.data_type() else {
+        out.push(field.clone());
+        return;
+    };
+    for child in children {
+        let name = format!("{}{separator}{}", field.name(), child.name());
+        let nullable = field.is_nullable() || child.is_nullable();
+        let child = Arc::new(
+            child
+                .as_ref()
+                .clone()
+                .with_name(name)
+                .with_nullable(nullable),
+        );
+        flatten_field(&child, separator, out);
+    }
+}
+
+/// Counterpart to [`flatten_schema`] for the arrays of a record batch. The nulls of each struct
+/// array are merged into its children, so they can be written without looking at the parent.
+pub fn flatten_arrays(arrays: &[ArrayRef], out: &mut Vec<ArrayRef>) {
+    for array in arrays {
+        flatten_array(array, out)
+    }
+}
+
+fn flatten_array(array: &ArrayRef, out: &mut Vec<ArrayRef>) {
+    let DataType::Struct(_) = array.data_type() else {
+        out.push(array.clone());
+        return;
+    };
+    let parent = array.as_struct();
+    for child in parent.columns() {
+        let child = match NullBuffer::union(parent.nulls(), child.nulls()) {
+            Some(nulls) if Some(&nulls) != child.nulls() => {
+                let data = child.to_data().into_builder().nulls(Some(nulls));
+                // Safety: Only the validity changed. It has the same length as the child.
+                make_array(unsafe { data.build_unchecked() })
+            }
+            _ => child.clone(),
+        };
+        flatten_array(&child, out);
+    }
+}
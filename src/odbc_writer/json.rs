@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{make_array, new_null_array, Array, ArrayRef, StringArray},
+    datatypes::{DataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+use arrow_json::{writer::LineDelimited, WriterBuilder};
+use odbc_api::buffers::{AnySliceMut, BufferDesc};
+
+use super::{text::Utf8ToNativeText, WriteStrategy, WriterError};
+
+/// Renders each element of a `List`, `LargeList`, `Struct` or `Map` array as JSON text and binds
+/// it like any other UTF-8 string. See [`crate::WriterOptions::nested_as_json`].
+pub struct NestedAsJson {
+    text: Utf8ToNativeText,
+}
+
+impl NestedAsJson {
+    /// Fails with [`WriterError::UnsupportedArrowDataType`] if `arrow-json` can not render
+    /// `data_type`, e.g. because it contains decimals or binary values.
+    pub fn new(data_type: &DataType) -> Result<Self, WriterError> {
+        // The JSON writer only inspects the data types once it renders a row, so we render a
+        // single `null`.
+        let probe = new_null_array(data_type, 1);
+        render_json(&probe)
+            .map_err(|_| WriterError::UnsupportedArrowDataType(data_type.clone()))?;
+        Ok(Self {
+            text: Utf8ToNativeText {},
+        })
+    }
+}
+
+impl WriteStrategy for NestedAsJson {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.text.buffer_desc()
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        column_buf: AnySliceMut<'_>,
+        array: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let json = render_json(&make_array(array.to_data()))?;
+        self.text.write_rows(param_offset, column_buf, &json)
+    }
+}
+
+/// Name of the only column of the record batch we hand to the JSON writer.
+const KEY: &str = "v";
+
+/// One JSON document for each non null element of `array`.
+fn render_json(array: &ArrayRef) -> Result<StringArray, WriterError> {
+    // `arrow-json` renders record batches as one JSON object per row, so we wrap `array` into a
+    // batch with a single column and strip the object from each line.
+    let schema = Schema::new(vec![Field::new(KEY, array.data_type().clone(), true)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![array.clone()])
+        .map_err(WriterError::RenderJson)?;
+    let mut writer = WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, LineDelimited>(Vec::new());
+    writer.write(&batch).map_err(WriterError::RenderJson)?;
+    let lines = String::from_utf8(writer.into_inner()).expect("JSON must be valid UTF-8");
+    // Newlines within strings are escaped, so each line holds exactly one row.
+    let prefix = format!("{{\"{KEY}\":");
+    let json = lines
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            if array.is_null(index) {
+                None
+            } else {
+                Some(&line[prefix.len()..line.len() - 1])
+            }
+        })
+        .collect();
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Array, ArrayRef, Float64Array, Int32Array, ListArray, StringArray, StructArray},
+        datatypes::{DataType, Field, Int32Type},
+    };
+
+    use crate::WriterError;
+
+    use super::{render_json, NestedAsJson};
+
+    fn documents(array: ArrayRef) -> Vec<Option<String>> {
+        render_json(&array)
+            .unwrap()
+            .iter()
+            .map(|json| json.map(str::to_owned))
+            .collect()
+    }
+
+    #[test]
+    fn list() {
+        let array = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2), None]),
+            None,
+            Some(vec![]),
+        ]);
+
+        let json = documents(Arc::new(array));
+
+        assert_eq!(
+            vec![Some("[1,2,null]".to_owned()), None, Some("[]".to_owned())],
+            json
+        );
+    }
+
+    #[test]
+    fn struct_with_escaped_text_and_non_finite_float() {
+        let x: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None]));
+        let y: ArrayRef = Arc::new(StringArray::from(vec![Some("a\"b\nc"), Some("d")]));
+        let z: ArrayRef = Arc::new(Float64Array::from(vec![0.5, f64::NAN]));
+        let array = StructArray::try_from(vec![("x", x), ("y", y), ("z", z)]).unwrap();
+
+        let json = documents(Arc::new(array));
+
+        assert_eq!(
+            vec![
+                Some(r#"{"x":1,"y":"a\"b\nc","z":0.5}"#.to_owned()),
+                Some(r#"{"x":null,"y":"d","z":null}"#.to_owned()),
+            ],
+            json
+        );
+    }
+
+    #[test]
+    fn unsupported_child_type() {
+        let item = Field::new("item", DataType::Decimal128(5, 2), true);
+        let data_type = DataType::List(Arc::new(item));
+
+        let result = NestedAsJson::new(&data_type);
+
+        assert!(matches!(
+            result,
+            Err(WriterError::UnsupportedArrowDataType(_))
+        ));
+    }
+
+    #[test]
+    fn empty_array() {
+        let array = ListArray::from_iter_primitive::<Int32Type, _, _>(Vec::<Option<Vec<_>>>::new());
+
+        assert_eq!(
+            0,
+            render_json(&(Arc::new(array) as ArrayRef)).unwrap().len()
+        );
+    }
+}
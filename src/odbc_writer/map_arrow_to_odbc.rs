@@ -20,6 +20,15 @@ pub trait MapArrowToOdbc {
     where
         U: Item;
 
+    /// Like [`Self::map_with`], but the conversion may fail, e.g. if the value is out of range for
+    /// the ODBC type.
+    fn try_map_with<U>(
+        nullable: bool,
+        arrow_to_odbc: impl Fn(Self::ArrowElement) -> Result<U, WriterError> + 'static,
+    ) -> Box<dyn WriteStrategy>
+    where
+        U: Item;
+
     fn identical(nullable: bool) -> Box<dyn WriteStrategy>
     where
         Self::ArrowElement: Item;
@@ -35,6 +44,16 @@ where
         nullable: bool,
         arrow_to_odbc: impl Fn(Self::ArrowElement) -> U + 'static,
     ) -> Box<dyn WriteStrategy>
+    where
+        U: Item,
+    {
+        Self::try_map_with(nullable, move |value| Ok(arrow_to_odbc(value)))
+    }
+
+    fn try_map_with<U>(
+        nullable: bool,
+        arrow_to_odbc: impl Fn(Self::ArrowElement) -> Result<U, WriterError> + 'static,
+    ) -> Box<dyn WriteStrategy>
     where
         U: Item,
     {
@@ -75,7 +94,7 @@ impl<P, F> Nullable<P, F> {
 impl<P, F, U> WriteStrategy for Nullable<P, F>
 where
     P: ArrowPrimitiveType,
-    F: Fn(P::Native) -> U,
+    F: Fn(P::Native) -> Result<U, WriterError>,
     U: Item,
 {
     fn buffer_desc(&self) -> BufferDesc {
@@ -91,7 +110,8 @@ where
         let from = array.as_any().downcast_ref::<PrimitiveArray<P>>().unwrap();
         let mut to = column_buf.as_nullable_slice::<U>().unwrap();
         for (index, cell) in from.iter().enumerate() {
-            to.set_cell(index + param_offset, cell.map(&self.arrow_to_odbc))
+            let value = cell.map(&self.arrow_to_odbc).transpose()?;
+            to.set_cell(index + param_offset, value)
         }
         Ok(())
     }
@@ -115,7 +135,7 @@ impl<P, F> NonNullable<P, F> {
 impl<P, F, U> WriteStrategy for NonNullable<P, F>
 where
     P: ArrowPrimitiveType,
-    F: Fn(P::Native) -> U,
+    F: Fn(P::Native) -> Result<U, WriterError>,
     U: Item,
 {
     fn buffer_desc(&self) -> BufferDesc {
@@ -131,7 +151,7 @@ where
         let from = array.as_any().downcast_ref::<PrimitiveArray<P>>().unwrap();
         let to = column_buf.as_slice::<U>().unwrap();
         for index in 0..from.len() {
-            to[index + param_offset] = (self.arrow_to_odbc)(from.value(index))
+            to[index + param_offset] = (self.arrow_to_odbc)(from.value(index))?
         }
         Ok(())
     }
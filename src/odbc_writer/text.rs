@@ -1,4 +1,6 @@
-use arrow::array::{Array, LargeStringArray, StringArray};
+use std::fmt::Write;
+
+use arrow::array::{Array, LargeStringArray, StringArray, UInt64Array};
 use odbc_api::buffers::{AnySliceMut, BufferDesc, TextColumnSliceMut};
 
 use super::{WriteStrategy, WriterError};
@@ -133,3 +135,41 @@ fn insert_into_wide_slice<'a>(
     }
     Ok(())
 }
+
+/// Binds `UInt64` arrays as decimal text, so values beyond `i64::MAX` can be inserted, e.g. into
+/// `DECIMAL(20,0)` columns. See [`crate::WriterOptions::uint64_as_text`].
+pub struct NullableUInt64AsText;
+
+impl NullableUInt64AsText {
+    /// Number of digits of `u64::MAX`
+    const STR_LEN: usize = 20;
+}
+
+impl WriteStrategy for NullableUInt64AsText {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            max_str_len: Self::STR_LEN,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        to: AnySliceMut<'_>,
+        from: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = from.as_any().downcast_ref::<UInt64Array>().unwrap();
+        let mut to = to.as_text_view().unwrap();
+        let mut text = String::with_capacity(Self::STR_LEN);
+        for (index, element) in from.iter().enumerate() {
+            if let Some(value) = element {
+                text.clear();
+                write!(text, "{value}").unwrap();
+                to.set_cell(index + param_offset, Some(text.as_bytes()))
+            } else {
+                to.set_cell(index + param_offset, None)
+            }
+        }
+        Ok(())
+    }
+}
@@ -8,12 +8,18 @@ pub struct Quirks {
     /// See: <https://github.com/pacman82/arrow-odbc-py/issues/68> and also
     /// <https://github.com/pacman82/odbc-api/issues/398>
     pub indicators_returned_from_bulk_fetch_are_memory_garbage: bool,
+    /// Fetch decimals as `SQL_NUMERIC_STRUCT` with the precision and scale of the Arrow field,
+    /// rather than as text. This saves us parsing the text and does not depend on the decimal
+    /// separator of the locale. Yet the driver must respect the precision and scale set in the
+    /// application row descriptor, which not every driver does correctly. So this is opt in.
+    pub fetch_decimals_as_numeric_struct: bool,
 }
 
 impl Quirks {
     pub fn new() -> Self {
         Self {
             indicators_returned_from_bulk_fetch_are_memory_garbage: false,
+            fetch_decimals_as_numeric_struct: false,
         }
     }
 }
@@ -3,8 +3,8 @@ use std::{convert::TryInto, sync::Arc};
 use arrow::{
     array::{ArrayRef, BooleanBuilder},
     datatypes::{
-        DataType as ArrowDataType, Date32Type, Field, Float32Type, Float64Type, Int16Type,
-        Int32Type, Int64Type, Int8Type, TimeUnit, TimestampMicrosecondType,
+        ArrowPrimitiveType, DataType as ArrowDataType, Date32Type, Field, Float32Type, Float64Type,
+        Int16Type, Int32Type, Int64Type, Int8Type, TimeUnit, TimestampMicrosecondType,
         TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt8Type,
     },
 };
@@ -17,26 +17,48 @@ use odbc_api::{
 use thiserror::Error;
 
 mod binary;
+mod cancel;
 mod concurrent_odbc_reader;
+mod conversion_thread;
 mod decimal;
+mod interval;
+#[cfg(feature = "json")]
+mod json;
 mod map_odbc_to_arrow;
 mod odbc_reader;
+mod partitioned_reader;
+mod prefetch;
+mod rejects;
+mod stats;
+mod struct_columns;
 mod text;
 mod to_record_batch;
+mod transit_buffer;
 
 use crate::{
     date_time::{
-        days_since_epoch, ms_since_epoch, ns_since_epoch, seconds_since_epoch, us_since_epoch,
+        date_to_naive, days_since_epoch, ms_since_epoch, seconds_since_epoch, timestamp_to_naive,
+        us_since_epoch,
     },
-    Quirks,
+    interval::IntervalKind,
+    OnInvalidDateTime, OutOfRangeTimestamp, Quirks,
 };
 
+#[cfg(feature = "json")]
+pub use self::json::JsonText;
+
 pub use self::{
     binary::{Binary, FixedSizedBinary},
+    cancel::{CancelHandle, Cancelled},
     concurrent_odbc_reader::ConcurrentOdbcReader,
-    decimal::Decimal,
-    map_odbc_to_arrow::{MapOdbcToArrow, MappingError},
+    decimal::{Decimal, NumericStructDecimal},
+    interval::Interval,
+    map_odbc_to_arrow::{ConversionError, MapOdbcToArrow, MappingError, RawValue, RowMappingError},
     odbc_reader::{OdbcReader, OdbcReaderBuilder},
+    partitioned_reader::{MergedReader, PartitionCursor, PartitionSpec, PartitionedReader},
+    rejects::{rejects_schema, OnConversionError, Reject},
+    stats::{BatchStats, ReaderStats},
+    struct_columns::StructFormat,
     text::choose_text_strategy,
 };
 
@@ -45,8 +67,35 @@ pub trait ReadStrategy {
     /// Describes the buffer which is bound to the ODBC cursor.
     fn buffer_desc(&self) -> BufferDesc;
 
-    /// Create an arrow array from an ODBC buffer described in [`Self::buffer_description`].
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError>;
+    /// Create an arrow array from an ODBC buffer described in [`Self::buffer_description`]. Errors
+    /// report the index of the offending row within the buffer.
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError>;
+
+    /// Like [`Self::fill_arrow_array`], yet values which can not be converted are pushed to
+    /// `rejects` instead of failing the entire array. They are replaced with `NULL` or, for non
+    /// nullable arrays, with a placeholder. Strategies which can not fail to convert a value do not
+    /// need to implement this.
+    fn fill_arrow_array_recording_rejects(
+        &self,
+        column_view: AnySlice,
+        _rejects: &mut Vec<RowMappingError>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        self.fill_arrow_array(column_view)
+    }
+
+    /// Precision and scale, if the column is to be fetched as `SQL_NUMERIC_STRUCT`. In this case
+    /// [`Self::buffer_desc`] must describe a binary buffer with elements of the size of a numeric
+    /// struct.
+    fn numeric_precision_and_scale(&self) -> Option<(u8, i8)> {
+        None
+    }
+
+    /// Kind of interval, if the column is to be fetched as `SQL_INTERVAL_STRUCT`. In this case
+    /// [`Self::buffer_desc`] must describe a binary buffer with elements of the size of an interval
+    /// struct.
+    fn interval_kind(&self) -> Option<IntervalKind> {
+        None
+    }
 }
 
 pub struct NonNullableBoolean;
@@ -56,7 +105,7 @@ impl ReadStrategy for NonNullableBoolean {
         BufferDesc::Bit { nullable: false }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
         let values = Bit::as_slice(column_view).unwrap();
         let mut builder = BooleanBuilder::new();
         for bit in values {
@@ -73,7 +122,7 @@ impl ReadStrategy for NullableBoolean {
         BufferDesc::Bit { nullable: true }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
         let values = Bit::as_nullable_slice(column_view).unwrap();
         let mut builder = BooleanBuilder::new();
         for bit in values {
@@ -83,6 +132,31 @@ impl ReadStrategy for NullableBoolean {
     }
 }
 
+/// Strategy for dates and timestamps. `convert` may only yield `None` if `nullable` is `true`.
+fn date_time_strategy<P, U>(
+    nullable: bool,
+    convert: impl Fn(&U) -> Result<Option<P::Native>, MappingError> + 'static + Send + Sync,
+) -> Box<dyn ReadStrategy + Send + Sync>
+where
+    P: ArrowPrimitiveType + Send + Sync,
+    U: Item + RawValue + 'static + Send + Sync,
+{
+    if nullable {
+        P::map_optional_with(convert)
+    } else {
+        P::map_with(false, move |value| {
+            Ok(convert(value)?.expect("Policies must not yield NULL for non nullable fields"))
+        })
+    }
+}
+
+/// Policies for dates and timestamps which can not be represented in Arrow.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct DateTimeOptions {
+    pub on_invalid_date_time: OnInvalidDateTime,
+    pub out_of_range_timestamp: OutOfRangeTimestamp,
+}
+
 /// Allows setting limits for buffers bound to the ODBC data source. Check this out if you find that
 /// you get memory allocation, or zero sized column errors. Used than constructing a reader using
 /// [`crate::OdbcReader::with`].
@@ -122,8 +196,13 @@ pub fn choose_column_strategy(
     col_index: u16,
     buffer_allocation_options: BufferAllocationOptions,
     quirks: &Quirks,
-) -> Result<Box<dyn ReadStrategy + Send>, ColumnFailure> {
-    let strat: Box<dyn ReadStrategy + Send> = match field.data_type() {
+    date_time_options: DateTimeOptions,
+) -> Result<Box<dyn ReadStrategy + Send + Sync>, ColumnFailure> {
+    let DateTimeOptions {
+        on_invalid_date_time,
+        out_of_range_timestamp,
+    } = date_time_options;
+    let strat: Box<dyn ReadStrategy + Send + Sync> = match field.data_type() {
         ArrowDataType::Boolean => {
             if field.is_nullable() {
                 Box::new(NullableBoolean)
@@ -139,26 +218,36 @@ pub fn choose_column_strategy(
         ArrowDataType::Float32 => Float32Type::identical(field.is_nullable()),
         ArrowDataType::Float64 => Float64Type::identical(field.is_nullable()),
         ArrowDataType::Date32 => {
-            Date32Type::map_with(field.is_nullable(), |e| Ok(days_since_epoch(e)))
+            let nullable = field.is_nullable();
+            date_time_strategy::<Date32Type, _>(nullable, move |value| {
+                let date = on_invalid_date_time.resolve(nullable, date_to_naive(value))?;
+                Ok(date.map(days_since_epoch))
+            })
         }
         ArrowDataType::Utf8 => {
-            let sql_type = query_metadata
-                .col_data_type(col_index)
-                .map_err(ColumnFailure::FailedToDescribeColumn)?;
-            // Use a zero based index here, because we use it everywhere else there we communicate
-            // with users.
-            debug!("Relational type of column {}: {sql_type:?}", col_index - 1);
-            let lazy_display_size = || query_metadata.col_display_size(col_index);
-            // Use the SQL type first to determine buffer length.
-            choose_text_strategy(
-                sql_type,
-                lazy_display_size,
-                buffer_allocation_options.max_text_size,
-                quirks.indicators_returned_from_bulk_fetch_are_memory_garbage,
-            )?
+            text_strategy(query_metadata, col_index, buffer_allocation_options, quirks)?
+        }
+        #[cfg(feature = "json")]
+        ArrowDataType::Struct(_) | ArrowDataType::List(_) | ArrowDataType::LargeList(_)
+            if JsonText::supports(field.data_type()) =>
+        {
+            let text = text_strategy(query_metadata, col_index, buffer_allocation_options, quirks)?;
+            Box::new(JsonText::new(
+                text,
+                field.data_type().clone(),
+                field.is_nullable(),
+            ))
         }
         ArrowDataType::Decimal128(precision, scale @ 0..) => {
-            Box::new(Decimal::new(*precision, *scale))
+            if quirks.fetch_decimals_as_numeric_struct {
+                Box::new(NumericStructDecimal::new(
+                    *precision,
+                    *scale,
+                    field.is_nullable(),
+                ))
+            } else {
+                Box::new(Decimal::new(*precision, *scale))
+            }
         }
         ArrowDataType::Binary => {
             let sql_type = query_metadata
@@ -180,20 +269,40 @@ pub fn choose_column_strategy(
             Box::new(Binary::new(length))
         }
         ArrowDataType::Timestamp(TimeUnit::Second, _) => {
-            TimestampSecondType::map_with(field.is_nullable(), |e| Ok(seconds_since_epoch(e)))
+            let nullable = field.is_nullable();
+            date_time_strategy::<TimestampSecondType, _>(nullable, move |value| {
+                let ndt = on_invalid_date_time.resolve(nullable, timestamp_to_naive(value))?;
+                Ok(ndt.map(seconds_since_epoch))
+            })
         }
         ArrowDataType::Timestamp(TimeUnit::Millisecond, _) => {
-            TimestampMillisecondType::map_with(field.is_nullable(), |e| Ok(ms_since_epoch(e)))
+            let nullable = field.is_nullable();
+            date_time_strategy::<TimestampMillisecondType, _>(nullable, move |value| {
+                let ndt = on_invalid_date_time.resolve(nullable, timestamp_to_naive(value))?;
+                Ok(ndt.map(ms_since_epoch))
+            })
         }
         ArrowDataType::Timestamp(TimeUnit::Microsecond, _) => {
-            TimestampMicrosecondType::map_with(field.is_nullable(), |e| Ok(us_since_epoch(e)))
+            let nullable = field.is_nullable();
+            date_time_strategy::<TimestampMicrosecondType, _>(nullable, move |value| {
+                let ndt = on_invalid_date_time.resolve(nullable, timestamp_to_naive(value))?;
+                Ok(ndt.map(us_since_epoch))
+            })
         }
         ArrowDataType::Timestamp(TimeUnit::Nanosecond, _) => {
-            TimestampNanosecondType::map_with(field.is_nullable(), ns_since_epoch)
+            let nullable = field.is_nullable();
+            date_time_strategy::<TimestampNanosecondType, _>(nullable, move |value| {
+                let ndt = on_invalid_date_time.resolve(nullable, timestamp_to_naive(value))?;
+                Ok(ndt
+                    .map(|ndt| out_of_range_timestamp.ns_since_epoch(nullable, ndt))
+                    .transpose()?
+                    .flatten())
+            })
         }
         ArrowDataType::FixedSizeBinary(length) => {
             Box::new(FixedSizedBinary::new((*length).try_into().unwrap()))
         }
+        ArrowDataType::Interval(unit) => Box::new(Interval::new(unit.clone(), field.is_nullable())),
         unsupported_arrow_type => {
             return Err(ColumnFailure::UnsupportedArrowType(
                 unsupported_arrow_type.clone(),
@@ -203,6 +312,29 @@ pub fn choose_column_strategy(
     Ok(strat)
 }
 
+/// Strategy for text columns, or columns whose text we parse, e.g. as JSON.
+fn text_strategy(
+    query_metadata: &mut impl ResultSetMetadata,
+    col_index: u16,
+    buffer_allocation_options: BufferAllocationOptions,
+    quirks: &Quirks,
+) -> Result<Box<dyn ReadStrategy + Send + Sync>, ColumnFailure> {
+    let sql_type = query_metadata
+        .col_data_type(col_index)
+        .map_err(ColumnFailure::FailedToDescribeColumn)?;
+    // Use a zero based index here, because we use it everywhere else there we communicate
+    // with users.
+    debug!("Relational type of column {}: {sql_type:?}", col_index - 1);
+    let lazy_display_size = || query_metadata.col_display_size(col_index);
+    // Use the SQL type first to determine buffer length.
+    choose_text_strategy(
+        sql_type,
+        lazy_display_size,
+        buffer_allocation_options.max_text_size,
+        quirks.indicators_returned_from_bulk_fetch_are_memory_garbage,
+    )
+}
+
 /// Read error related to a specific column
 #[derive(Error, Debug)]
 pub enum ColumnFailure {
@@ -239,6 +371,13 @@ pub enum ColumnFailure {
         attached to the ODBC result set:\n{0}"
     )]
     FailedToDescribeColumn(#[source] odbc_api::Error),
+    /// A struct could either be parsed from JSON text or assembled from several columns, and its
+    /// format has not been chosen explicitly.
+    #[error(
+        "The struct could either be parsed from JSON text or assembled from one column for each of \
+        its leaves. Choose its format explicitly using `OdbcReaderBuilder::with_struct_format`."
+    )]
+    AmbiguousStructFormat,
     #[error(
         "Column buffer is too large to be allocated. Tried to alloacte {num_elements} elements \
         with {element_size} bytes in size each."
@@ -0,0 +1,196 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use arrow::{error::ArrowError, record_batch::RecordBatch};
+use odbc_api::{
+    handles::{AsHandle, SqlResult},
+    sys::{HStmt, Handle, HandleType, SQLCancel, SqlReturn},
+};
+use thiserror::Error;
+
+/// Emitted by [`crate::OdbcReader`] and [`crate::ConcurrentOdbcReader`] as the last item of the
+/// iteration, after the read has been cancelled via a [`CancelHandle`]. It is wrapped in an
+/// [`ArrowError::ExternalError`].
+#[derive(Debug, Error)]
+#[error("Reading the result set has been cancelled.")]
+pub struct Cancelled;
+
+/// Allows to cancel a read from another thread. Obtained via [`crate::OdbcReader::cancel_handle`]
+/// or [`crate::ConcurrentOdbcReader::cancel_handle`]. Cancelling calls `SQLCancel` on the
+/// statement the reader is fetching from. This aborts a fetch which may currently be in progress,
+/// assuming the driver supports it. Any subsequent call to `next` on the reader will yield a
+/// [`Cancelled`] error and then end the iteration.
+///
+/// It is fine for the handle to outlive the reader. Cancelling after the reader has been dropped,
+/// turned back into a cursor, or failed to fetch a batch, has no effect.
+#[derive(Clone)]
+pub struct CancelHandle {
+    state: Arc<CancelState>,
+}
+
+impl CancelHandle {
+    /// Cancel the read. See [`CancelHandle`].
+    pub fn cancel(&self) -> Result<(), odbc_api::Error> {
+        // Set the flag first, so the reader interprets any error caused by `SQLCancel` correctly.
+        self.state.cancelled.store(true, Ordering::SeqCst);
+        self.state.cancel_statement()
+    }
+}
+
+/// State shared between a reader and all of its cancel handles.
+struct CancelState {
+    /// Statement handle the reader fetches from. `None` once the reader no longer owns the
+    /// statement.
+    statement: Mutex<Option<StatementPtr>>,
+    /// `true` once any handle has been used to cancel the read.
+    cancelled: AtomicBool,
+}
+
+impl CancelState {
+    /// Calls `SQLCancel` on the statement, unless it already has been removed.
+    fn cancel_statement(&self) -> Result<(), odbc_api::Error> {
+        // Hold the lock while calling `SQLCancel` so the statement can not be freed in between.
+        let statement = self.statement.lock().unwrap();
+        if let Some(statement) = statement.as_ref() {
+            // Safety: The statement handle is valid as long as it is set in the shared state. ODBC
+            // explicitly allows `SQLCancel` to be called from a different thread than the one
+            // executing a function on the statement.
+            match unsafe { SQLCancel(statement.0) } {
+                SqlReturn::SUCCESS | SqlReturn::SUCCESS_WITH_INFO => (),
+                _ => SqlResult::<()>::Error {
+                    function: "SQLCancel",
+                }
+                .into_result(statement)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Owned by the reader. Hands out [`CancelHandle`]s and decides how the reader reacts to
+/// cancellation.
+pub struct Cancellation {
+    state: Arc<CancelState>,
+    /// `true` once we emitted the [`Cancelled`] error.
+    reported: bool,
+}
+
+impl Cancellation {
+    /// The [`StatementGuard`] must be owned by whoever owns the statement.
+    pub fn new(statement: HStmt) -> (Self, StatementGuard) {
+        let state = Arc::new(CancelState {
+            statement: Mutex::new(Some(StatementPtr(statement))),
+            cancelled: AtomicBool::new(false),
+        });
+        let guard = StatementGuard {
+            state: state.clone(),
+        };
+        let cancellation = Self {
+            state,
+            reported: false,
+        };
+        (cancellation, guard)
+    }
+
+    pub fn handle(&self) -> CancelHandle {
+        CancelHandle {
+            state: self.state.clone(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// The item the reader should emit after it has been cancelled. A [`Cancelled`] error the first
+    /// time, `None` afterwards.
+    pub fn report(&mut self) -> Option<Result<RecordBatch, ArrowError>> {
+        if self.reported {
+            None
+        } else {
+            self.reported = true;
+            Some(Err(ArrowError::ExternalError(Box::new(Cancelled))))
+        }
+    }
+}
+
+/// Owned together with the statement, i.e. by the reader or by the thread fetching from it. Removes
+/// the statement from the shared state then dropped, so it must be dropped before the statement is
+/// freed.
+pub struct StatementGuard {
+    state: Arc<CancelState>,
+}
+
+impl StatementGuard {
+    /// A handle to cancel the statement, e.g. before waiting for the thread owning it to finish.
+    pub fn handle(&self) -> CancelHandle {
+        CancelHandle {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Runs `f`, which frees the statement if it fails, e.g. unbinding a buffer. Cancel handles wait
+    /// until `f` returns, and have no effect after it failed.
+    pub fn free_on_error<T, E>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let mut statement = self.state.statement.lock().unwrap();
+        let result = f();
+        if result.is_err() {
+            statement.take();
+        }
+        result
+    }
+}
+
+impl Drop for StatementGuard {
+    fn drop(&mut self) {
+        self.state.statement.lock().unwrap().take();
+    }
+}
+
+/// Raw statement handle, so we can send it to the threads owning the cancel handles.
+struct StatementPtr(HStmt);
+
+// Safety: We only ever use the handle to call `SQLCancel` or to fetch diagnostics, which is
+// allowed from any thread.
+unsafe impl Send for StatementPtr {}
+
+unsafe impl AsHandle for StatementPtr {
+    fn as_handle(&self) -> Handle {
+        self.0 as Handle
+    }
+
+    fn handle_type(&self) -> HandleType {
+        HandleType::Stmt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cancellation;
+
+    #[test]
+    fn statement_is_removed_if_freed_on_error() {
+        // Never passed to `SQLCancel`, since we only cancel after the statement has been removed.
+        let (_cancellation, guard) = Cancellation::new(std::ptr::null_mut());
+
+        let result = guard.free_on_error(|| Err::<(), _>("unbind failed"));
+
+        assert!(result.is_err());
+        assert!(guard.state.statement.lock().unwrap().is_none());
+        guard.handle().cancel().unwrap();
+    }
+
+    #[test]
+    fn statement_is_kept_if_not_freed() {
+        let (_cancellation, guard) = Cancellation::new(std::ptr::null_mut());
+
+        guard.free_on_error(|| Ok::<_, ()>(())).unwrap();
+
+        assert!(guard.state.statement.lock().unwrap().is_some());
+        let state = guard.state.clone();
+        drop(guard);
+        assert!(state.statement.lock().unwrap().is_none());
+    }
+}
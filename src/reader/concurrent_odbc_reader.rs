@@ -1,13 +1,24 @@
+use std::mem::take;
+
 use arrow::{
     datatypes::SchemaRef,
     error::ArrowError,
     record_batch::{RecordBatch, RecordBatchReader},
 };
-use odbc_api::{buffers::ColumnarAnyBuffer, BlockCursor, ConcurrentBlockCursor, Cursor};
+use odbc_api::{BlockCursor, Cursor};
 
 use crate::Error;
 
-use super::to_record_batch::ToRecordBatch;
+use super::{
+    cancel::{CancelHandle, Cancellation, StatementGuard},
+    conversion_thread::{fetch_and_convert, ConversionThread},
+    odbc_reader::limit_rows,
+    prefetch::PrefetchBlockCursor,
+    rejects::{rejects_to_record_batch, Reject},
+    stats::{ReaderStats, StatsTracker},
+    to_record_batch::ToRecordBatch,
+    transit_buffer::TransitBuffer,
+};
 
 /// Arrow ODBC reader. Implements the [`arrow::record_batch::RecordBatchReader`] trait so it can be
 /// used to fill Arrow arrays from an ODBC data source. Similar to [`crate::OdbcReader`], yet
@@ -16,7 +27,10 @@ use super::to_record_batch::ToRecordBatch;
 /// application logic to run on the main thread, while fetching the batches from the source happens
 /// concurrently. You need twice the memory for the transit buffer for this strategy, since one is
 /// may be in use by the main thread in order to copy values into arrow arrays, while the other is
-/// used to write values from the database.
+/// used to write values from the database. Use [`crate::OdbcReaderBuilder::with_prefetch_depth`] to
+/// allow the fetch thread to fill even more buffers ahead of the application. Use
+/// [`crate::OdbcReaderBuilder::with_background_conversion`] to also move the conversion into Arrow
+/// arrays off the application thread.
 ///
 /// # Example
 ///
@@ -65,38 +79,93 @@ use super::to_record_batch::ToRecordBatch;
 /// }
 /// ```
 pub struct ConcurrentOdbcReader<C: Cursor> {
-    /// We fill the buffers using ODBC concurrently. The buffer currently being filled is bound to
-    /// the Cursor. This is the buffer which is unbound and read by the application to fill the
-    /// arrow arrays. After being read we will reuse the buffer and bind it to the cursor in order
-    /// to safe allocations.
-    buffer: ColumnarAnyBuffer,
-    /// Converts the content of ODBC buffers into Arrow record batches
-    converter: ToRecordBatch,
-    /// Fetches values from the ODBC datasource using columnar batches. Values are streamed batch
-    /// by batch in order to avoid reallocation of the buffers used for tranistion.
-    batch_stream: ConcurrentBlockCursor<C>,
+    /// Arrow schema of the emitted record batches
+    schema: SchemaRef,
+    /// Allows cancelling the read from other threads.
+    cancellation: Cancellation,
+    /// Either converts the batches fetched concurrently on the application thread, or receives
+    /// batches which already have been converted in a dedicated system thread.
+    source: Source<C>,
+    /// Number of rows we are still allowed to emit. `None` means there is no limit.
+    remaining_rows: Option<usize>,
+    /// Fetch and conversion statistics
+    stats: StatsTracker,
+    /// Values which could not be converted, recorded since the last call to `take_rejects`.
+    rejects: Vec<Reject>,
+}
+
+enum Source<C> {
+    /// Only fetching happens concurrently. Conversion into Arrow arrays happens on the application
+    /// thread.
+    Fetched {
+        /// We fill the buffers using ODBC concurrently. The buffer currently being filled is bound
+        /// to the Cursor. This is the buffer which is unbound and read by the application to fill
+        /// the arrow arrays. After being read we will reuse the buffer and bind it to the cursor in
+        /// order to safe allocations.
+        buffer: TransitBuffer,
+        /// Converts the content of ODBC buffers into Arrow record batches
+        converter: ToRecordBatch,
+        /// Fetches values from the ODBC datasource using columnar batches. Values are streamed
+        /// batch by batch in order to avoid reallocation of the buffers used for tranistion.
+        batch_stream: PrefetchBlockCursor<C>,
+    },
+    /// Fetching and conversion into Arrow arrays both happen in dedicated system threads.
+    Converted(ConversionThread<C>),
 }
 
 impl<C: Cursor + Send + 'static> ConcurrentOdbcReader<C> {
     /// The schema implied by `block_cursor` and `converter` must match. Invariant is hard to check
     /// in type system, keep this constructor private to this crate. Users should use
     /// [`crate::OdbcReader::into_concurrent`] instead.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_block_cursor(
-        block_cursor: BlockCursor<C, ColumnarAnyBuffer>,
+        block_cursor: BlockCursor<C, TransitBuffer>,
         converter: ToRecordBatch,
+        cancellation: Cancellation,
+        statement_guard: StatementGuard,
         fallibale_allocations: bool,
+        prefetch_depth: usize,
+        background_conversion: bool,
+        remaining_rows: Option<usize>,
+        stats: StatsTracker,
+        rejects: Vec<Reject>,
     ) -> Result<Self, Error> {
         let max_batch_size = block_cursor.row_array_size();
-        let batch_stream = ConcurrentBlockCursor::from_block_cursor(block_cursor);
+        let mut batch_stream =
+            PrefetchBlockCursor::new(block_cursor, statement_guard, prefetch_depth);
         // Note that we delay buffer allocation until after the fetch thread has started and we
         // start fetching the first row group concurrently as early, not waiting for the buffer
         // allocation to go through.
         let buffer = converter.allocate_buffer(max_batch_size, fallibale_allocations)?;
+        // Spare buffers, the fetch thread can fill ahead of the application.
+        for _ in 1..prefetch_depth {
+            batch_stream.fill(converter.allocate_buffer(max_batch_size, fallibale_allocations)?);
+        }
+
+        let schema = converter.schema().clone();
+        let source = if background_conversion {
+            Source::Converted(ConversionThread::new(
+                batch_stream,
+                buffer,
+                converter,
+                prefetch_depth,
+                stats.cumulative().num_rows,
+            ))
+        } else {
+            Source::Fetched {
+                buffer,
+                converter,
+                batch_stream,
+            }
+        };
 
         Ok(Self {
-            buffer,
-            converter,
-            batch_stream,
+            schema,
+            cancellation,
+            source,
+            remaining_rows,
+            stats,
+            rejects,
         })
     }
 
@@ -109,7 +178,30 @@ impl<C: Cursor + Send + 'static> ConcurrentOdbcReader<C> {
     /// extracted once the cursor is returned. Unless that is that the entire cursor has been
     /// consumed i.e. [`Self::next`] returned `None`.
     pub fn into_cursor(self) -> Result<C, odbc_api::Error> {
-        self.batch_stream.into_cursor()
+        match self.source {
+            Source::Fetched { batch_stream, .. } => batch_stream.into_cursor(),
+            Source::Converted(conversion_thread) => conversion_thread.into_cursor(),
+        }
+    }
+
+    /// A handle which can be send to other threads in order to cancel the read. After cancelling,
+    /// the reader emits a [`crate::Cancelled`] error and ends the iteration. Dropping the reader
+    /// cancels a fetch which may still be in progress in the background thread, too.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancellation.handle()
+    }
+
+    /// Statistics accumulated over all batches fetched so far. Since fetching happens concurrently,
+    /// the fetch duration is the time the converting thread had been blocked, waiting for the next
+    /// batch.
+    pub fn stats(&self) -> &ReaderStats {
+        self.stats.cumulative()
+    }
+
+    /// Values which could not be converted into their Arrow target type since the last call to
+    /// this method. See [`crate::OdbcReader::take_rejects`].
+    pub fn take_rejects(&mut self) -> RecordBatch {
+        rejects_to_record_batch(take(&mut self.rejects))
     }
 }
 
@@ -120,18 +212,50 @@ where
     type Item = Result<RecordBatch, ArrowError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.batch_stream.fetch_into(&mut self.buffer) {
-            // We successfully fetched a batch from the database. Try to copy it into a record batch
-            // and forward errors if any.
-            Ok(true) => {
-                let result_record_batch = self
-                    .converter
-                    .buffer_to_record_batch(&self.buffer)
-                    .map_err(|mapping_error| ArrowError::ExternalError(Box::new(mapping_error)));
+        // Do not fetch any more batches, if we already emitted all the rows the user asked for.
+        if self.remaining_rows == Some(0) {
+            return None;
+        }
+        if self.cancellation.is_cancelled() {
+            return self.cancellation.report();
+        }
+        let converted = match &mut self.source {
+            Source::Fetched {
+                buffer,
+                converter,
+                batch_stream,
+            } => fetch_and_convert(
+                batch_stream,
+                buffer,
+                converter,
+                self.stats.cumulative().num_rows,
+            ),
+            Source::Converted(conversion_thread) => conversion_thread.fetch(),
+        };
+        match converted {
+            // We successfully fetched a batch from the database and tried to copy it into a record
+            // batch. Forward errors if any.
+            Ok(Some(mut converted)) => {
+                self.rejects.append(&mut converted.rejects);
+                self.stats.record(
+                    converted.num_rows,
+                    converted.transit_bytes,
+                    converted.fetch_duration,
+                    converted.conversion_durations,
+                );
+                let result_record_batch = converted
+                    .record_batch
+                    .map(|batch| limit_rows(batch, &mut self.remaining_rows))
+                    .map_err(|conversion_error| {
+                        ArrowError::ExternalError(Box::new(conversion_error))
+                    });
                 Some(result_record_batch)
             }
             // We ran out of batches in the result set. End the iterator.
-            Ok(false) => None,
+            Ok(None) => None,
+            // The error is likely caused by the fetch being cancelled. Report the cancellation
+            // rather than the error the driver chose for it.
+            Err(_) if self.cancellation.is_cancelled() => self.cancellation.report(),
             // We had an error fetching the next batch from the database, let's report it as an
             // external error.
             Err(odbc_error) => Some(Err(ArrowError::ExternalError(Box::new(odbc_error)))),
@@ -144,6 +268,6 @@ where
     C: Cursor,
 {
     fn schema(&self) -> SchemaRef {
-        self.converter.schema().clone()
+        self.schema.clone()
     }
 }
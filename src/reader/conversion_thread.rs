@@ -0,0 +1,167 @@
+use std::{
+    sync::mpsc::{sync_channel, Receiver},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use arrow::record_batch::RecordBatch;
+use odbc_api::Cursor;
+
+use super::{
+    cancel::CancelHandle, prefetch::PrefetchBlockCursor, to_record_batch::ToRecordBatch,
+    transit_buffer::TransitBuffer, ConversionError, Reject,
+};
+
+/// A batch fetched from the data source and converted into a record batch, together with the
+/// information we need to keep track of statistics.
+pub struct ConvertedBatch {
+    pub record_batch: Result<RecordBatch, ConversionError>,
+    /// Number of rows in the transit buffer
+    pub num_rows: usize,
+    /// See [`TransitBuffer::transferred_bytes`]
+    pub transit_bytes: usize,
+    pub fetch_duration: Duration,
+    pub conversion_durations: Vec<Duration>,
+    /// Values which could not be converted
+    pub rejects: Vec<Reject>,
+}
+
+/// Receives the next batch from `batch_stream` into `buffer` and converts it into a record batch.
+/// `None` if the result set is consumed. `first_row_index` is the number of rows fetched so far.
+pub fn fetch_and_convert<C>(
+    batch_stream: &mut PrefetchBlockCursor<C>,
+    buffer: &mut TransitBuffer,
+    converter: &ToRecordBatch,
+    first_row_index: usize,
+) -> Result<Option<ConvertedBatch>, odbc_api::Error> {
+    let start_fetch = Instant::now();
+    if !batch_stream.fetch_into(buffer)? {
+        return Ok(None);
+    }
+    let fetch_duration = start_fetch.elapsed();
+    let mut conversion_durations = Vec::new();
+    let mut rejects = Vec::new();
+    let record_batch = converter.buffer_to_record_batch(
+        buffer,
+        first_row_index,
+        &mut conversion_durations,
+        &mut rejects,
+    );
+    Ok(Some(ConvertedBatch {
+        record_batch,
+        num_rows: buffer.num_rows(),
+        transit_bytes: buffer.transferred_bytes(),
+        fetch_duration,
+        conversion_durations,
+        rejects,
+    }))
+}
+
+/// Converts the batches received from a [`PrefetchBlockCursor`] into record batches in a dedicated
+/// system thread. The application thread only receives the finished record batches.
+///
+/// Like [`PrefetchBlockCursor`], dropping it cancels a fetch which may be in progress and joins the
+/// conversion thread, which in turn joins the fetch thread.
+pub struct ConversionThread<C> {
+    /// Receives converted batches, or the error which occurred fetching them. Once the source is
+    /// empty or an error occurred, the associated sender is dropped.
+    receive_batch: Receiver<Result<ConvertedBatch, odbc_api::Error>>,
+    /// We join with the conversion thread if we stop receiving batches or `into_cursor` is called.
+    /// `None` if the thread has already been joined.
+    conversion_thread: Option<JoinHandle<PrefetchBlockCursor<C>>>,
+    /// Only `Some`, if `conversion_thread` has been joined.
+    batch_stream: Option<PrefetchBlockCursor<C>>,
+    /// Cancels a fetch in progress, so we do not wait for it to finish then dropped.
+    cancel_handle: CancelHandle,
+}
+
+impl<C> ConversionThread<C>
+where
+    C: Cursor + Send + 'static,
+{
+    /// Up to `depth` converted record batches are held ahead of the application. `num_rows` is the
+    /// number of rows fetched before, so row indices count from the start of the cursor.
+    pub fn new(
+        mut batch_stream: PrefetchBlockCursor<C>,
+        mut buffer: TransitBuffer,
+        converter: ToRecordBatch,
+        depth: usize,
+        mut num_rows: usize,
+    ) -> Self {
+        let (send_batch, receive_batch) = sync_channel(depth);
+        let cancel_handle = batch_stream.cancel_handle();
+        let conversion_thread = thread::spawn(move || {
+            loop {
+                let converted =
+                    fetch_and_convert(&mut batch_stream, &mut buffer, &converter, num_rows);
+                if let Ok(Some(converted)) = &converted {
+                    num_rows += converted.num_rows;
+                }
+                let is_last = !matches!(converted, Ok(Some(_)));
+                if let Some(converted) = converted.transpose() {
+                    if send_batch.send(converted).is_err() {
+                        // The application stopped receiving batches. So we stop converting them.
+                        break;
+                    }
+                }
+                if is_last {
+                    break;
+                }
+            }
+            batch_stream
+        });
+        Self {
+            receive_batch,
+            conversion_thread: Some(conversion_thread),
+            batch_stream: None,
+            cancel_handle,
+        }
+    }
+
+    /// Join conversion and fetch thread and yield the cursor back.
+    pub fn into_cursor(mut self) -> Result<C, odbc_api::Error> {
+        self.hang_up();
+        let batch_stream = if let Some(batch_stream) = self.batch_stream.take() {
+            batch_stream
+        } else {
+            self.conversion_thread.take().unwrap().join().unwrap()
+        };
+        batch_stream.into_cursor()
+    }
+}
+
+impl<C> ConversionThread<C> {
+    /// Replaces our end of the channel with a disconnected one. Sending the next batch fails, so the
+    /// conversion thread stops and returns.
+    fn hang_up(&mut self) {
+        let (_, receive_batch) = sync_channel(0);
+        self.receive_batch = receive_batch;
+    }
+
+    /// Receive the next converted batch. `None` if the cursor is consumed. Blocks until a batch is
+    /// available.
+    pub fn fetch(&mut self) -> Result<Option<ConvertedBatch>, odbc_api::Error> {
+        match self.receive_batch.recv() {
+            Ok(converted) => converted.map(Some),
+            // Conversion thread stopped sending batches, because we consumed the result set.
+            Err(_receive_error) => {
+                if let Some(join_handle) = self.conversion_thread.take() {
+                    self.batch_stream = Some(join_handle.join().unwrap());
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<C> Drop for ConversionThread<C> {
+    fn drop(&mut self) {
+        self.hang_up();
+        if let Some(conversion_thread) = self.conversion_thread.take() {
+            // Do not wait for a fetch in progress to finish.
+            let _ = self.cancel_handle.cancel();
+            // Errors have no one left to be reported to. Panics would only cause a double panic.
+            let _ = conversion_thread.join();
+        }
+    }
+}
@@ -1,12 +1,17 @@
 use std::sync::Arc;
 
-use arrow::array::{ArrayRef, Decimal128Builder};
+use arrow::{
+    array::{ArrayRef, Decimal128Builder},
+    datatypes::DataType,
+};
 use odbc_api::{
     buffers::{AnySlice, BufferDesc},
     decimal_text_to_i128,
 };
 
-use super::{MappingError, ReadStrategy};
+use crate::numeric::{numeric_struct_to_i128, numeric_struct_to_string};
+
+use super::{transit_buffer::TransitBuffer, MappingError, ReadStrategy, RowMappingError};
 
 pub struct Decimal {
     precision: u8,
@@ -28,7 +33,7 @@ impl ReadStrategy for Decimal {
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
         let view = column_view.as_text_view().unwrap();
         let mut builder = Decimal128Builder::new();
         let scale = self.scale as usize;
@@ -50,3 +55,173 @@ impl ReadStrategy for Decimal {
         ))
     }
 }
+
+/// Fetches decimals as `SQL_NUMERIC_STRUCT` and converts the 128 Bit mantissa directly, rather
+/// than parsing text. See [`crate::Quirks::fetch_decimals_as_numeric_struct`].
+pub struct NumericStructDecimal {
+    precision: u8,
+    scale: i8,
+    nullable: bool,
+}
+
+impl NumericStructDecimal {
+    pub fn new(precision: u8, scale: i8, nullable: bool) -> Self {
+        Self {
+            precision,
+            scale,
+            nullable,
+        }
+    }
+
+    /// Values which can not be converted are pushed to `rejects`, if it is `Some`, and replaced
+    /// with `NULL` or, for non nullable fields, a placeholder. Otherwise the first such value is
+    /// reported as an error.
+    fn convert(
+        &self,
+        column_view: AnySlice,
+        mut rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let view = column_view.as_bin_view().unwrap();
+        let mut builder = Decimal128Builder::with_capacity(view.len());
+
+        for (row_index, opt) in view.iter().enumerate() {
+            let Some(bytes) = opt else {
+                builder.append_null();
+                continue;
+            };
+            match numeric_struct_to_i128(bytes, self.scale) {
+                Some(value) => builder.append_value(value),
+                None => {
+                    let error = RowMappingError::new(
+                        row_index,
+                        numeric_struct_to_string(bytes),
+                        MappingError::OutOfRangeDecimal {
+                            arrow_type: DataType::Decimal128(self.precision, self.scale),
+                        },
+                    );
+                    let Some(rejects) = rejects.as_mut() else {
+                        return Err(error);
+                    };
+                    rejects.push(error);
+                    if self.nullable {
+                        builder.append_null();
+                    } else {
+                        // The field is not nullable, so the row is going to be dropped.
+                        builder.append_value(0);
+                    }
+                }
+            }
+        }
+
+        Ok(Arc::new(
+            builder
+                .finish()
+                .with_precision_and_scale(self.precision, self.scale)
+                .unwrap(),
+        ))
+    }
+}
+
+impl ReadStrategy for NumericStructDecimal {
+    fn buffer_desc(&self) -> BufferDesc {
+        TransitBuffer::numeric_buffer_desc()
+    }
+
+    fn numeric_precision_and_scale(&self) -> Option<(u8, i8)> {
+        Some((self.precision, self.scale))
+    }
+
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, None)
+    }
+
+    fn fill_arrow_array_recording_rejects(
+        &self,
+        column_view: AnySlice,
+        rejects: &mut Vec<RowMappingError>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, Some(rejects))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Array, ArrayRef, AsArray},
+        datatypes::Decimal128Type,
+    };
+    use odbc_api::buffers::{AnyBuffer, BinColumn, ColumnBuffer};
+
+    use crate::{
+        numeric::{i128_to_numeric_struct, NUMERIC_STRUCT_SIZE},
+        reader::{MappingError, ReadStrategy, RowMappingError},
+    };
+
+    use super::NumericStructDecimal;
+
+    /// Converts the values as if fetched as numeric structs, recording rejects if `rejects` is
+    /// `Some`. The second value does not fit into 128 Bit.
+    fn convert(
+        strategy: &NumericStructDecimal,
+        rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let mut too_large = i128_to_numeric_struct(0, 38, 2);
+        too_large[3..].fill(u8::MAX);
+        let mut column = BinColumn::new(3, NUMERIC_STRUCT_SIZE);
+        column.set_value(0, Some(&i128_to_numeric_struct(12345, 5, 2)));
+        column.set_value(1, Some(&too_large));
+        column.set_value(2, None);
+        let buffer = AnyBuffer::Binary(column);
+        let view = buffer.view(3);
+        match rejects {
+            None => strategy.fill_arrow_array(view),
+            Some(rejects) => strategy.fill_arrow_array_recording_rejects(view, rejects),
+        }
+    }
+
+    #[test]
+    fn out_of_range_fails_array() {
+        let strategy = NumericStructDecimal::new(38, 2, true);
+
+        let result = convert(&strategy, None);
+
+        assert!(matches!(
+            result,
+            Err(RowMappingError {
+                row_index: 1,
+                source: MappingError::OutOfRangeDecimal { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn out_of_range_is_recorded_as_null() {
+        let strategy = NumericStructDecimal::new(38, 2, true);
+        let mut rejects = Vec::new();
+
+        let array = convert(&strategy, Some(&mut rejects)).unwrap();
+
+        let decimals = array.as_primitive::<Decimal128Type>();
+        assert_eq!(12345, decimals.value(0));
+        assert!(decimals.is_null(1));
+        assert!(decimals.is_null(2));
+        assert_eq!(1, rejects.len());
+        assert_eq!(1, rejects[0].row_index);
+        assert_eq!(
+            "3402823669209384634633746074317682114.55",
+            rejects[0].raw_value
+        );
+    }
+
+    #[test]
+    fn out_of_range_is_recorded_with_placeholder_if_not_nullable() {
+        let strategy = NumericStructDecimal::new(38, 2, false);
+        let mut rejects = Vec::new();
+
+        let array = convert(&strategy, Some(&mut rejects)).unwrap();
+
+        assert!(array.is_valid(1));
+        assert_eq!(1, rejects.len());
+    }
+}
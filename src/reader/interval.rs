@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, PrimitiveArray, PrimitiveBuilder},
+    datatypes::{
+        ArrowPrimitiveType, DataType, IntervalDayTimeType, IntervalMonthDayNanoType, IntervalUnit,
+        IntervalYearMonthType,
+    },
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use crate::interval::{
+    interval_struct_to_days_and_nanos, interval_struct_to_months, interval_struct_to_string,
+    IntervalKind,
+};
+
+use super::{transit_buffer::TransitBuffer, MappingError, ReadStrategy, RowMappingError};
+
+/// Fetches intervals as `SQL_INTERVAL_STRUCT`. `YearMonth` intervals are fetched as
+/// `SQL_C_INTERVAL_YEAR_TO_MONTH`, `DayTime` and `MonthDayNano` intervals as
+/// `SQL_C_INTERVAL_DAY_TO_SECOND`.
+pub struct Interval {
+    unit: IntervalUnit,
+    nullable: bool,
+}
+
+impl Interval {
+    pub fn new(unit: IntervalUnit, nullable: bool) -> Self {
+        Self { unit, nullable }
+    }
+
+    /// Values which can not be converted are pushed to `rejects`, if it is `Some`, and replaced
+    /// with `NULL` or, for non nullable fields, a placeholder. Otherwise the first such value is
+    /// reported as an error.
+    fn convert(
+        &self,
+        column_view: AnySlice,
+        rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let array: ArrayRef = match self.unit {
+            IntervalUnit::YearMonth => Arc::new(self.build::<IntervalYearMonthType>(
+                column_view,
+                rejects,
+                interval_struct_to_months,
+            )?),
+            IntervalUnit::DayTime => {
+                Arc::new(
+                    self.build::<IntervalDayTimeType>(column_view, rejects, |bytes| {
+                        let (days, nanos) = interval_struct_to_days_and_nanos(bytes)?;
+                        let milliseconds = (nanos / 1_000_000).try_into().ok()?;
+                        Some(IntervalDayTimeType::make_value(days, milliseconds))
+                    })?,
+                )
+            }
+            IntervalUnit::MonthDayNano => {
+                Arc::new(
+                    self.build::<IntervalMonthDayNanoType>(column_view, rejects, |bytes| {
+                        let (days, nanos) = interval_struct_to_days_and_nanos(bytes)?;
+                        Some(IntervalMonthDayNanoType::make_value(0, days, nanos))
+                    })?,
+                )
+            }
+        };
+        Ok(array)
+    }
+
+    /// Builds an array of `P` from the interval structs in `column_view`. `convert` is `None` if
+    /// the value is out of range for `P`.
+    fn build<P: ArrowPrimitiveType>(
+        &self,
+        column_view: AnySlice,
+        mut rejects: Option<&mut Vec<RowMappingError>>,
+        convert: impl Fn(&[u8]) -> Option<P::Native>,
+    ) -> Result<PrimitiveArray<P>, RowMappingError> {
+        let view = column_view.as_bin_view().unwrap();
+        let mut builder = PrimitiveBuilder::<P>::with_capacity(view.len());
+        for (row_index, opt) in view.iter().enumerate() {
+            let Some(bytes) = opt else {
+                builder.append_null();
+                continue;
+            };
+            match convert(bytes) {
+                Some(value) => builder.append_value(value),
+                None => {
+                    let error = RowMappingError::new(
+                        row_index,
+                        interval_struct_to_string(bytes),
+                        MappingError::OutOfRangeInterval {
+                            arrow_type: DataType::Interval(self.unit.clone()),
+                        },
+                    );
+                    let Some(rejects) = rejects.as_mut() else {
+                        return Err(error);
+                    };
+                    rejects.push(error);
+                    if self.nullable {
+                        builder.append_null();
+                    } else {
+                        // The field is not nullable, so the row is going to be dropped.
+                        builder.append_value(P::Native::default());
+                    }
+                }
+            }
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl ReadStrategy for Interval {
+    fn buffer_desc(&self) -> BufferDesc {
+        TransitBuffer::interval_buffer_desc()
+    }
+
+    fn interval_kind(&self) -> Option<IntervalKind> {
+        match self.unit {
+            IntervalUnit::YearMonth => Some(IntervalKind::YearToMonth),
+            IntervalUnit::DayTime | IntervalUnit::MonthDayNano => Some(IntervalKind::DayToSecond),
+        }
+    }
+
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, None)
+    }
+
+    fn fill_arrow_array_recording_rejects(
+        &self,
+        column_view: AnySlice,
+        rejects: &mut Vec<RowMappingError>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, Some(rejects))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Array, ArrayRef, AsArray},
+        datatypes::{IntervalMonthDayNanoType, IntervalUnit},
+    };
+    use odbc_api::buffers::{AnyBuffer, BinColumn, ColumnBuffer};
+
+    use crate::{
+        interval::{days_and_nanos_to_interval_struct, INTERVAL_STRUCT_SIZE},
+        reader::{MappingError, ReadStrategy, RowMappingError},
+    };
+
+    use super::Interval;
+
+    const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+    /// Converts the values as if fetched as day to second interval structs, recording rejects if
+    /// `rejects` is `Some`. The days of the second value do not fit into 32 Bit.
+    fn convert(
+        strategy: &Interval,
+        rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let mut column = BinColumn::new(3, INTERVAL_STRUCT_SIZE);
+        column.set_value(0, Some(&days_and_nanos_to_interval_struct(1, 2)));
+        column.set_value(
+            1,
+            Some(&days_and_nanos_to_interval_struct(i32::MIN, -NANOS_PER_DAY)),
+        );
+        column.set_value(2, None);
+        let buffer = AnyBuffer::Binary(column);
+        let view = buffer.view(3);
+        match rejects {
+            None => strategy.fill_arrow_array(view),
+            Some(rejects) => strategy.fill_arrow_array_recording_rejects(view, rejects),
+        }
+    }
+
+    #[test]
+    fn out_of_range_fails_array() {
+        let strategy = Interval::new(IntervalUnit::MonthDayNano, true);
+
+        let result = convert(&strategy, None);
+
+        assert!(matches!(
+            result,
+            Err(RowMappingError {
+                row_index: 1,
+                source: MappingError::OutOfRangeInterval { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn out_of_range_is_recorded_as_null() {
+        let strategy = Interval::new(IntervalUnit::MonthDayNano, true);
+        let mut rejects = Vec::new();
+
+        let array = convert(&strategy, Some(&mut rejects)).unwrap();
+
+        let intervals = array.as_primitive::<IntervalMonthDayNanoType>();
+        assert_eq!(
+            IntervalMonthDayNanoType::make_value(0, 1, 2),
+            intervals.value(0)
+        );
+        assert!(intervals.is_null(1));
+        assert!(intervals.is_null(2));
+        assert_eq!(1, rejects.len());
+        assert_eq!(1, rejects[0].row_index);
+        assert_eq!("-2147483649 00:00:00.000000000", rejects[0].raw_value);
+    }
+
+    #[test]
+    fn out_of_range_is_recorded_with_placeholder_if_not_nullable() {
+        let strategy = Interval::new(IntervalUnit::DayTime, false);
+        let mut rejects = Vec::new();
+
+        let array = convert(&strategy, Some(&mut rejects)).unwrap();
+
+        assert!(array.is_valid(1));
+        assert_eq!(1, rejects.len());
+    }
+}
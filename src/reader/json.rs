@@ -0,0 +1,448 @@
+use std::fmt::Write;
+
+use arrow::{
+    array::{new_empty_array, Array, ArrayRef, AsArray},
+    datatypes::{DataType, Field},
+};
+use arrow_json::ReaderBuilder;
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, RowMappingError};
+
+/// Fetches text, e.g. from PostgreSQL `json` or MySQL `JSON` columns, and parses each value as JSON
+/// into a `Struct`, `List` or `LargeList` array using `arrow-json`. Members of JSON objects without
+/// a matching field are ignored. Missing members are `NULL`.
+pub struct JsonText {
+    /// Fetches the text. Chosen like for any other `Utf8` field.
+    text: Box<dyn ReadStrategy + Send + Sync>,
+    field: Field,
+    /// Document standing in for values which can not be parsed, if the field is not nullable. The
+    /// row is going to be dropped, but the array must still be valid.
+    placeholder: String,
+}
+
+impl JsonText {
+    pub fn new(
+        text: Box<dyn ReadStrategy + Send + Sync>,
+        data_type: DataType,
+        nullable: bool,
+    ) -> Self {
+        let placeholder = if nullable {
+            "null".to_owned()
+        } else {
+            placeholder(&data_type)
+        };
+        Self {
+            text,
+            field: Field::new("json", data_type, nullable),
+            placeholder,
+        }
+    }
+
+    /// `true` if JSON can be parsed into an array of `data_type`. Leaves may be booleans, integers,
+    /// floats or strings.
+    pub fn supports(data_type: &DataType) -> bool {
+        match data_type {
+            DataType::Struct(fields) => {
+                fields.iter().all(|field| Self::supports(field.data_type()))
+            }
+            DataType::List(field) | DataType::LargeList(field) => Self::supports(field.data_type()),
+            other => is_leaf(other),
+        }
+    }
+
+    /// Values which can not be parsed are pushed to `rejects`, if it is `Some`, and replaced with
+    /// `NULL` or, for non nullable fields, a placeholder. Otherwise the first such value is
+    /// reported as an error.
+    fn convert(
+        &self,
+        column_view: AnySlice,
+        rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let text = self.text.fill_arrow_array(column_view)?;
+        let text = text.as_string::<i32>();
+        // Most of the time every document is valid, so we try to parse them all at once first.
+        if let Ok(array) = self.parse(text.iter()) {
+            return Ok(array);
+        }
+        // Parse the documents one by one, to find out which rows are invalid.
+        let mut documents = Vec::with_capacity(text.len());
+        let mut rejects = rejects;
+        for (row_index, opt) in text.iter().enumerate() {
+            let document = match self.parse([opt]) {
+                Ok(_) => opt,
+                Err(message) => {
+                    let error = RowMappingError::new(
+                        row_index,
+                        opt.unwrap_or_default().to_owned(),
+                        MappingError::InvalidJson { message },
+                    );
+                    let Some(rejects) = rejects.as_mut() else {
+                        return Err(error);
+                    };
+                    rejects.push(error);
+                    Some(self.placeholder.as_str())
+                }
+            };
+            documents.push(document);
+        }
+        Ok(self
+            .parse(documents)
+            .expect("Valid documents and placeholders must parse"))
+    }
+
+    /// Parses each document into one element of the array. `None` is parsed as `null`. Fails if any
+    /// document is not valid JSON, does not match the data type, or holds more than one value.
+    fn parse<'a>(
+        &self,
+        documents: impl IntoIterator<Item = Option<&'a str>>,
+    ) -> Result<ArrayRef, String> {
+        let documents = documents.into_iter();
+        let num_documents = documents.size_hint().0;
+        let mut decoder = ReaderBuilder::new_with_field(self.field.clone())
+            .with_batch_size(num_documents.max(1))
+            .build_decoder()
+            .map_err(|error| error.to_string())?;
+        for document in documents {
+            let document = document.unwrap_or("null");
+            check_delimited(document)?;
+            // Stops consuming input once the batch is full, i.e. if a document held several values.
+            let consumed = decoder
+                .decode(document.as_bytes())
+                .map_err(|error| error.to_string())?;
+            // Values are separated by whitespace. Without it `1` followed by `2` would be `12`.
+            let separator = decoder.decode(b"\n").map_err(|error| error.to_string())?;
+            if consumed != document.len() || separator != 1 {
+                return Err(MULTIPLE_VALUES.to_owned());
+            }
+        }
+        let Some(batch) = decoder.flush().map_err(|error| error.to_string())? else {
+            return Ok(new_empty_array(self.field.data_type()));
+        };
+        if batch.num_rows() != num_documents {
+            return Err(MULTIPLE_VALUES.to_owned());
+        }
+        Ok(batch.column(0).clone())
+    }
+}
+
+impl ReadStrategy for JsonText {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.text.buffer_desc()
+    }
+
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, None)
+    }
+
+    fn fill_arrow_array_recording_rejects(
+        &self,
+        column_view: AnySlice,
+        rejects: &mut Vec<RowMappingError>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, Some(rejects))
+    }
+}
+
+const MULTIPLE_VALUES: &str = "expected exactly one JSON value";
+
+/// Maximum number of nested arrays and objects within a document. `arrow-json` parses documents
+/// without recursion, yet describes offending values recursively in its error messages.
+const MAX_DEPTH: usize = 128;
+
+/// The decoder reads a stream of values, so a document must neither be empty nor end within a
+/// value. Otherwise its value would be merged with the one of the next document. Also rejects
+/// documents nested deeper than [`MAX_DEPTH`]. Brackets are only counted, so this does not
+/// recurse.
+fn check_delimited(document: &str) -> Result<(), String> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut empty = true;
+    for byte in document.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > MAX_DEPTH {
+                    return Err(format!("exceeds maximum nesting depth of {MAX_DEPTH}"));
+                }
+            }
+            b'}' | b']' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| "unbalanced brackets".to_owned())?
+            }
+            _ => (),
+        }
+        empty &= byte.is_ascii_whitespace();
+    }
+    if empty {
+        Err("empty document".to_owned())
+    } else if in_string || depth != 0 {
+        Err("unexpected end of document".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+fn is_leaf(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Utf8
+            | DataType::LargeUtf8
+    )
+}
+
+/// A valid JSON document for a non nullable field of `data_type`.
+fn placeholder(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Struct(fields) => {
+            let mut object = String::from("{");
+            for field in fields.iter().filter(|field| !field.is_nullable()) {
+                if object.len() != 1 {
+                    object.push(',');
+                }
+                write_json_string(field.name(), &mut object);
+                object.push(':');
+                object.push_str(&placeholder(field.data_type()));
+            }
+            object.push('}');
+            object
+        }
+        DataType::List(_) | DataType::LargeList(_) => "[]".to_owned(),
+        DataType::Boolean => "false".to_owned(),
+        DataType::Utf8 | DataType::LargeUtf8 => "\"\"".to_owned(),
+        _ => "0".to_owned(),
+    }
+}
+
+fn write_json_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Array, ArrayRef, AsArray},
+        datatypes::{DataType, Field, Fields, Int64Type},
+    };
+    use odbc_api::buffers::{AnyBuffer, ColumnBuffer, TextColumn};
+
+    use crate::reader::{text::NarrowText, MappingError, ReadStrategy, RowMappingError};
+
+    use super::JsonText;
+
+    /// Long enough for the deeply nested document.
+    const MAX_STR_LEN: usize = 1 << 21;
+
+    fn list_of_int64(nullable: bool) -> JsonText {
+        let item = Arc::new(Field::new("item", DataType::Int64, true));
+        json_text(DataType::List(item), nullable)
+    }
+
+    fn json_text(data_type: DataType, nullable: bool) -> JsonText {
+        JsonText::new(Box::new(NarrowText::new(MAX_STR_LEN)), data_type, nullable)
+    }
+
+    /// Parses `documents` as if fetched from a text column. Invalid documents are recorded as
+    /// rejects, if `rejects` is `Some`.
+    fn convert(
+        strategy: &JsonText,
+        documents: &[Option<&str>],
+        rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let mut column = TextColumn::new(documents.len(), MAX_STR_LEN);
+        for (index, document) in documents.iter().enumerate() {
+            column.set_value(index, document.map(str::as_bytes));
+        }
+        let buffer = AnyBuffer::Text(column);
+        let view = buffer.view(documents.len());
+        match rejects {
+            None => strategy.fill_arrow_array(view),
+            Some(rejects) => strategy.fill_arrow_array_recording_rejects(view, rejects),
+        }
+    }
+
+    fn invalid_json_row(result: Result<ArrayRef, RowMappingError>) -> usize {
+        match result {
+            Err(RowMappingError {
+                row_index,
+                source: MappingError::InvalidJson { .. },
+                ..
+            }) => row_index,
+            other => panic!("Expected invalid JSON, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_lists() {
+        let strategy = list_of_int64(true);
+
+        let array = convert(&strategy, &[Some("[1, 2]"), None, Some(" [] ")], None).unwrap();
+
+        let list = array.as_list::<i32>();
+        assert_eq!(3, list.len());
+        assert_eq!(&[1, 2], list.value(0).as_primitive::<Int64Type>().values());
+        assert!(list.is_null(1));
+        assert_eq!(0, list.value(2).len());
+    }
+
+    #[test]
+    fn parse_struct_ignoring_unknown_members() {
+        let fields = Fields::from(vec![
+            Field::new("x", DataType::Int64, true),
+            Field::new("y", DataType::Utf8, true),
+        ]);
+        let strategy = json_text(DataType::Struct(fields), true);
+
+        let array = convert(
+            &strategy,
+            &[Some(r#"{"x": 1, "z": [[{}]]}"#), Some(r#"{"y": "A"}"#)],
+            None,
+        )
+        .unwrap();
+
+        let array = array.as_struct();
+        let x = array.column(0).as_primitive::<Int64Type>();
+        let y = array.column(1).as_string::<i32>();
+        assert_eq!(1, x.value(0));
+        assert!(x.is_null(1));
+        assert!(y.is_null(0));
+        assert_eq!("A", y.value(1));
+    }
+
+    #[test]
+    fn report_row_of_invalid_document() {
+        let strategy = list_of_int64(true);
+
+        let result = convert(&strategy, &[Some("[1]"), Some(r#"[1, "two"]"#)], None);
+
+        assert_eq!(1, invalid_json_row(result));
+    }
+
+    #[test]
+    fn reject_malformed_unicode_escape() {
+        let item = Arc::new(Field::new("item", DataType::Utf8, true));
+        let strategy = json_text(DataType::List(item), true);
+
+        let result = convert(&strategy, &[Some(r#"["\u+041"]"#)], None);
+
+        assert_eq!(0, invalid_json_row(result));
+    }
+
+    #[test]
+    fn reject_deeply_nested_document() {
+        let strategy = list_of_int64(true);
+        let depth = 1_000_000;
+        let document = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+        let result = convert(&strategy, &[Some(&document)], None);
+
+        assert_eq!(0, invalid_json_row(result));
+    }
+
+    #[test]
+    fn reject_unbalanced_document() {
+        let strategy = list_of_int64(true);
+        let documents = [
+            Some("[1, 2"),
+            Some("3]"),
+            Some("[1] [2]"),
+            Some(""),
+            Some("[1]]"),
+        ];
+
+        for document in documents {
+            let result = convert(&strategy, &[document, Some("[4]")], None);
+
+            assert_eq!(0, invalid_json_row(result), "{document:?}");
+        }
+    }
+
+    #[test]
+    fn reject_null_for_non_nullable_field() {
+        let strategy = list_of_int64(false);
+
+        let result = convert(&strategy, &[Some("[1]"), Some("null")], None);
+
+        assert_eq!(1, invalid_json_row(result));
+    }
+
+    #[test]
+    fn record_rejects() {
+        let mut rejects = Vec::new();
+
+        let nullable = convert(
+            &list_of_int64(true),
+            &[Some("[1]"), Some("{"), Some("[3]")],
+            Some(&mut rejects),
+        )
+        .unwrap();
+        let non_nullable = convert(
+            &list_of_int64(false),
+            &[Some("[1]"), Some("{"), Some("[3]")],
+            Some(&mut rejects),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![1, 1],
+            rejects.iter().map(|r| r.row_index).collect::<Vec<_>>()
+        );
+        assert!(nullable.is_null(1));
+        assert_eq!(0, non_nullable.null_count());
+        assert_eq!(3, non_nullable.len());
+    }
+
+    #[test]
+    fn placeholder_for_struct_with_non_nullable_children() {
+        let inner = Fields::from(vec![Field::new("a\"b", DataType::Boolean, false)]);
+        let fields = Fields::from(vec![
+            Field::new("x", DataType::Int64, false),
+            Field::new("y", DataType::Struct(inner), false),
+            Field::new("z", DataType::Utf8, true),
+        ]);
+        let strategy = json_text(DataType::Struct(fields), false);
+        let mut rejects = Vec::new();
+
+        let array = convert(&strategy, &[Some("[]")], Some(&mut rejects)).unwrap();
+
+        assert_eq!(1, rejects.len());
+        assert_eq!(1, array.len());
+        assert_eq!(0, array.null_count());
+    }
+}
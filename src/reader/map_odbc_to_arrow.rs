@@ -1,11 +1,16 @@
 use std::{marker::PhantomData, sync::Arc};
 
 use arrow::{
-    array::{ArrayRef, PrimitiveBuilder},
-    datatypes::ArrowPrimitiveType,
+    array::{ArrayRef, PrimitiveArray, PrimitiveBuilder},
+    buffer::{BooleanBuffer, Buffer, NullBuffer, ScalarBuffer},
+    datatypes::{ArrowNativeType, ArrowPrimitiveType, DataType},
+    error::ArrowError,
 };
 use chrono::NaiveDateTime;
-use odbc_api::buffers::{AnySlice, BufferDesc, Item};
+use odbc_api::{
+    buffers::{AnySlice, BufferDesc, Item},
+    sys::{Date, Timestamp, NULL_DATA},
+};
 use thiserror::Error;
 
 use super::ReadStrategy;
@@ -18,39 +23,64 @@ pub trait MapOdbcToArrow {
     /// element of an arrow array.
     fn map_with<U>(
         nullable: bool,
-        odbc_to_arrow: impl Fn(&U) -> Result<Self::ArrowElement, MappingError> + 'static + Send,
-    ) -> Box<dyn ReadStrategy + Send>
+        odbc_to_arrow: impl Fn(&U) -> Result<Self::ArrowElement, MappingError> + 'static + Send + Sync,
+    ) -> Box<dyn ReadStrategy + Send + Sync>
+    where
+        U: Item + RawValue + 'static + Send + Sync;
+
+    /// Like [`Self::map_with`], yet the provided function may map a value to `NULL`. The resulting
+    /// strategy is always nullable.
+    fn map_optional_with<U>(
+        odbc_to_arrow: impl Fn(&U) -> Result<Option<Self::ArrowElement>, MappingError>
+            + 'static
+            + Send
+            + Sync,
+    ) -> Box<dyn ReadStrategy + Send + Sync>
     where
-        U: Item + 'static + Send;
+        U: Item + RawValue + 'static + Send + Sync;
 
     /// Should the arrow array element be identical to an item in the ODBC buffer no mapping is
     /// needed. We still need to account for nullability.
-    fn identical(nullable: bool) -> Box<dyn ReadStrategy + Send>
+    fn identical(nullable: bool) -> Box<dyn ReadStrategy + Send + Sync>
     where
         Self::ArrowElement: Item;
 }
 
 impl<T> MapOdbcToArrow for T
 where
-    T: ArrowPrimitiveType + Send,
+    T: ArrowPrimitiveType + Send + Sync,
 {
     type ArrowElement = T::Native;
 
     fn map_with<U>(
         nullable: bool,
-        odbc_to_arrow: impl Fn(&U) -> Result<Self::ArrowElement, MappingError> + 'static + Send,
-    ) -> Box<dyn ReadStrategy + Send>
+        odbc_to_arrow: impl Fn(&U) -> Result<Self::ArrowElement, MappingError> + 'static + Send + Sync,
+    ) -> Box<dyn ReadStrategy + Send + Sync>
     where
-        U: Item + 'static + Send,
+        U: Item + RawValue + 'static + Send + Sync,
     {
         if nullable {
-            Box::new(NullableStrategy::<Self, U, _>::new(odbc_to_arrow))
+            Box::new(NullableStrategy::<Self, U, _>::new(move |value: &U| {
+                odbc_to_arrow(value).map(Some)
+            }))
         } else {
             Box::new(NonNullableStrategy::<Self, U, _>::new(odbc_to_arrow))
         }
     }
 
-    fn identical(nullable: bool) -> Box<dyn ReadStrategy + Send>
+    fn map_optional_with<U>(
+        odbc_to_arrow: impl Fn(&U) -> Result<Option<Self::ArrowElement>, MappingError>
+            + 'static
+            + Send
+            + Sync,
+    ) -> Box<dyn ReadStrategy + Send + Sync>
+    where
+        U: Item + RawValue + 'static + Send + Sync,
+    {
+        Box::new(NullableStrategy::<Self, U, _>::new(odbc_to_arrow))
+    }
+
+    fn identical(nullable: bool) -> Box<dyn ReadStrategy + Send + Sync>
     where
         Self::ArrowElement: Item,
     {
@@ -76,18 +106,19 @@ impl<T> NonNullDirectStrategy<T> {
 
 impl<T> ReadStrategy for NonNullDirectStrategy<T>
 where
-    T: ArrowPrimitiveType + Send,
+    T: ArrowPrimitiveType + Send + Sync,
     T::Native: Item,
 {
     fn buffer_desc(&self) -> BufferDesc {
         T::Native::buffer_desc(false)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
         let slice = T::Native::as_slice(column_view).unwrap();
-        let mut builder = PrimitiveBuilder::<T>::with_capacity(slice.len());
-        builder.append_slice(slice);
-        Ok(Arc::new(builder.finish()))
+        Ok(Arc::new(PrimitiveArray::<T>::new(
+            copy_values_to_buffer(slice),
+            None,
+        )))
     }
 }
 
@@ -105,23 +136,51 @@ impl<T> NullableDirectStrategy<T> {
 
 impl<T> ReadStrategy for NullableDirectStrategy<T>
 where
-    T: ArrowPrimitiveType + Send,
+    T: ArrowPrimitiveType + Send + Sync,
     T::Native: Item,
 {
     fn buffer_desc(&self) -> BufferDesc {
         T::Native::buffer_desc(true)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
-        let values = T::Native::as_nullable_slice(column_view).unwrap();
-        let mut builder = PrimitiveBuilder::<T>::with_capacity(values.len());
-        for value in values {
-            builder.append_option(value.copied());
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
+        let (values, indicators) = T::Native::as_nullable_slice(column_view)
+            .unwrap()
+            .raw_values();
+        let nulls = NullBuffer::new(BooleanBuffer::collect_bool(indicators.len(), |index| {
+            indicators[index] != NULL_DATA
+        }));
+        if nulls.null_count() == 0 {
+            return Ok(Arc::new(PrimitiveArray::<T>::new(
+                copy_values_to_buffer(values),
+                None,
+            )));
         }
-        Ok(Arc::new(builder.finish()))
+        // Values at the position of `NULL`s are whatever the buffer held before. Zero them, so the
+        // array content does not depend on previous batches.
+        let mut values = values.to_vec();
+        for index in (!nulls.inner()).set_indices() {
+            values[index] = T::Native::default();
+        }
+        Ok(Arc::new(PrimitiveArray::<T>::new(
+            ScalarBuffer::from(values),
+            Some(nulls),
+        )))
     }
 }
 
+/// Copies the values of an ODBC column buffer into an Arrow buffer with a single `memcpy`. This is
+/// not zero-copy. We can not hand over the ODBC buffer itself, since every reader, sequential or
+/// concurrent, reuses its transit buffers to fetch the next batch, and `odbc-api` does not allow us
+/// to take ownership of the memory of a single column. A reader which allocates a new transit
+/// buffer for each batch would trade the copy for an allocation of the same size.
+fn copy_values_to_buffer<T>(values: &[T]) -> ScalarBuffer<T>
+where
+    T: ArrowNativeType,
+{
+    ScalarBuffer::new(Buffer::from_slice_ref(values), 0, values.len())
+}
+
 struct NonNullableStrategy<P, O, F> {
     _primitive_type: PhantomData<P>,
     _odbc_item: PhantomData<O>,
@@ -138,23 +197,59 @@ impl<P, O, F> NonNullableStrategy<P, O, F> {
     }
 }
 
+impl<P, O, F> NonNullableStrategy<P, O, F>
+where
+    P: ArrowPrimitiveType,
+    O: Item + RawValue,
+    F: Fn(&O) -> Result<P::Native, MappingError>,
+{
+    /// Values which can not be converted are pushed to `rejects`, if it is `Some`, and replaced
+    /// with a placeholder. Otherwise the first such value is reported as an error.
+    fn convert(
+        &self,
+        column_view: AnySlice,
+        mut rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let slice = column_view.as_slice::<O>().unwrap();
+        let mut builder = PrimitiveBuilder::<P>::with_capacity(slice.len());
+        for (row_index, odbc_value) in slice.iter().enumerate() {
+            match (self.odbc_to_arrow)(odbc_value) {
+                Ok(value) => builder.append_value(value),
+                Err(source) => {
+                    let error = RowMappingError::new(row_index, odbc_value.render(), source);
+                    let Some(rejects) = rejects.as_mut() else {
+                        return Err(error);
+                    };
+                    rejects.push(error);
+                    // The field is not nullable, so the row is going to be dropped.
+                    builder.append_value(P::Native::default());
+                }
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
 impl<P, O, F> ReadStrategy for NonNullableStrategy<P, O, F>
 where
     P: ArrowPrimitiveType + Send,
-    O: Item + Send,
+    O: Item + RawValue + Send,
     F: Fn(&O) -> Result<P::Native, MappingError> + Send,
 {
     fn buffer_desc(&self) -> BufferDesc {
         O::buffer_desc(false)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
-        let slice = column_view.as_slice::<O>().unwrap();
-        let mut builder = PrimitiveBuilder::<P>::with_capacity(slice.len());
-        for odbc_value in slice {
-            builder.append_value((self.odbc_to_arrow)(odbc_value)?);
-        }
-        Ok(Arc::new(builder.finish()))
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, None)
+    }
+
+    fn fill_arrow_array_recording_rejects(
+        &self,
+        column_view: AnySlice,
+        rejects: &mut Vec<RowMappingError>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, Some(rejects))
     }
 }
 
@@ -174,29 +269,69 @@ impl<P, O, F> NullableStrategy<P, O, F> {
     }
 }
 
+impl<P, O, F> NullableStrategy<P, O, F>
+where
+    P: ArrowPrimitiveType,
+    O: Item + RawValue,
+    F: Fn(&O) -> Result<Option<P::Native>, MappingError>,
+{
+    /// Values which can not be converted are pushed to `rejects`, if it is `Some`, and replaced
+    /// with `NULL`. Otherwise the first such value is reported as an error.
+    fn convert(
+        &self,
+        column_view: AnySlice,
+        mut rejects: Option<&mut Vec<RowMappingError>>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        let opts = column_view.as_nullable_slice::<O>().unwrap();
+        let mut builder = PrimitiveBuilder::<P>::with_capacity(opts.len());
+        for (row_index, odbc_opt) in opts.enumerate() {
+            let Some(odbc_value) = odbc_opt else {
+                builder.append_null();
+                continue;
+            };
+            match (self.odbc_to_arrow)(odbc_value) {
+                Ok(value) => builder.append_option(value),
+                Err(source) => {
+                    let error = RowMappingError::new(row_index, odbc_value.render(), source);
+                    let Some(rejects) = rejects.as_mut() else {
+                        return Err(error);
+                    };
+                    rejects.push(error);
+                    builder.append_null();
+                }
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
 impl<P, O, F> ReadStrategy for NullableStrategy<P, O, F>
 where
     P: ArrowPrimitiveType + Send,
-    O: Item + Send,
-    F: Fn(&O) -> Result<P::Native, MappingError> + Send,
+    O: Item + RawValue + Send,
+    F: Fn(&O) -> Result<Option<P::Native>, MappingError> + Send,
 {
     fn buffer_desc(&self) -> BufferDesc {
         O::buffer_desc(true)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
-        let opts = column_view.as_nullable_slice::<O>().unwrap();
-        let mut builder = PrimitiveBuilder::<P>::with_capacity(opts.len());
-        for odbc_opt in opts {
-            builder.append_option(odbc_opt.map(&self.odbc_to_arrow).transpose()?);
-        }
-        Ok(Arc::new(builder.finish()))
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, None)
+    }
+
+    fn fill_arrow_array_recording_rejects(
+        &self,
+        column_view: AnySlice,
+        rejects: &mut Vec<RowMappingError>,
+    ) -> Result<ArrayRef, RowMappingError> {
+        self.convert(column_view, Some(rejects))
     }
 }
 
 /// The source value returned from the ODBC datasource is out of range and can not be mapped into
 /// its Arrow target type.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum MappingError {
     #[error(
         "Timestamp is not representable in arrow: {value}\n\
@@ -206,4 +341,127 @@ pub enum MappingError {
         Suggestions to fix this error either reduce the precision or fetch the values as text."
     )]
     OutOfRangeTimestampNs { value: NaiveDateTime },
+    /// Drivers may return dates which do not exist, e.g. MySQL returns `0000-00-00` for zero
+    /// dates.
+    #[error(
+        "The data source returned an invalid date: {year:04}-{month:02}-{day:02}. You may want to \
+        replace such values with NULL or a sentinel using \
+        `OdbcReaderBuilder::with_on_invalid_date_time`."
+    )]
+    InvalidDate { year: i16, month: u16, day: u16 },
+    /// Drivers may return times which do not exist, e.g. a leap second represented with `60`
+    /// seconds.
+    #[error(
+        "The data source returned an invalid time: {hour:02}:{minute:02}:{second:02} and \
+        {fraction} nanoseconds. You may want to replace such values with NULL or a sentinel using \
+        `OdbcReaderBuilder::with_on_invalid_date_time`."
+    )]
+    InvalidTime {
+        hour: u16,
+        minute: u16,
+        second: u16,
+        fraction: u32,
+    },
+    /// Intervals fetched as `SQL_INTERVAL_STRUCT` may hold more years or days than the 32 Bit
+    /// fields of Arrow intervals.
+    #[error(
+        "The data source returned an interval which is not representable as Arrow {arrow_type}. \
+        Suggestions to fix this error either use a different interval unit or fetch the values as \
+        text."
+    )]
+    OutOfRangeInterval { arrow_type: DataType },
+    /// Decimals fetched as `SQL_NUMERIC_STRUCT` may have a mantissa exceeding 128 Bits, or a
+    /// different scale than requested, which can not be changed without losing digits.
+    #[error(
+        "The data source returned a numeric value which is not representable as Arrow {arrow_type} \
+        without losing digits. Suggestions to fix this error either choose a precision and scale \
+        matching the column or fetch the values as text."
+    )]
+    OutOfRangeDecimal { arrow_type: DataType },
+    /// Text fetched for a nested field is not valid JSON, or does not match the nested type.
+    #[error("The data source returned text which can not be parsed as JSON of the requested type: {message}")]
+    InvalidJson { message: String },
+}
+
+/// A [`MappingError`] together with the position of the offending value within the column buffer.
+/// Emitted by read strategies, which know the row, but not the column they are converting.
+#[derive(Debug)]
+pub struct RowMappingError {
+    /// Zero based index of the row within the batch.
+    pub row_index: usize,
+    /// The value in the ODBC buffer, rendered as text. See [`crate::Reject::raw_value`].
+    pub raw_value: String,
+    pub source: MappingError,
+}
+
+impl RowMappingError {
+    pub fn new(row_index: usize, raw_value: String, source: MappingError) -> Self {
+        Self {
+            row_index,
+            raw_value,
+            source,
+        }
+    }
+}
+
+/// Renders an element of an ODBC buffer for [`RowMappingError::raw_value`]. Values which can not be
+/// converted are usually not valid for the Arrow type, so we render their fields as is, e.g.
+/// `2023-02-30`.
+pub trait RawValue {
+    fn render(&self) -> String;
+}
+
+impl RawValue for Date {
+    fn render(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl RawValue for Timestamp {
+    fn render(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:09}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second, self.fraction
+        )
+    }
+}
+
+/// A [`MappingError`] with the precise position of the value which could not be converted. Emitted
+/// by [`crate::OdbcReader`] and [`crate::ConcurrentOdbcReader`] wrapped in an
+/// [`ArrowError::ExternalError`]. Use [`ConversionError::from_arrow_error`] to get it back out.
+#[derive(Error, Debug)]
+#[error(
+    "Failed to convert the value in column '{column_name}' (index {column_index}) at row \
+    {row_index} of the batch (row {absolute_row_index} of the result set).\n{source}"
+)]
+pub struct ConversionError {
+    /// Zero based index of the column.
+    pub column_index: usize,
+    /// Name of the field in the Arrow schema.
+    pub column_name: String,
+    /// Zero based index of the row within the record batch.
+    pub row_index: usize,
+    /// Zero based index of the row, counting from the start of the cursor.
+    pub absolute_row_index: usize,
+    pub source: MappingError,
+}
+
+impl ConversionError {
+    /// The structured error, should `error` have been caused by failing to convert a value.
+    ///
+    /// ```
+    /// use arrow_odbc::{arrow::error::ArrowError, ConversionError};
+    ///
+    /// fn report(error: &ArrowError) {
+    ///     if let Some(conversion_error) = ConversionError::from_arrow_error(error) {
+    ///         eprintln!("Invalid value in column {}", conversion_error.column_name);
+    ///     }
+    /// }
+    /// ```
+    pub fn from_arrow_error(error: &ArrowError) -> Option<&ConversionError> {
+        match error {
+            ArrowError::ExternalError(source) => source.downcast_ref(),
+            _ => None,
+        }
+    }
 }
@@ -1,15 +1,31 @@
-use std::cmp::min;
+use std::{
+    cmp::{max, min},
+    collections::HashMap,
+    mem::take,
+    sync::Arc,
+    time::Instant,
+};
 
 use arrow::{
     datatypes::SchemaRef,
     error::ArrowError,
     record_batch::{RecordBatch, RecordBatchReader},
 };
-use odbc_api::{buffers::ColumnarAnyBuffer, BlockCursor, Cursor};
+use odbc_api::{handles::Statement, BlockCursor, Cursor};
 
-use crate::{BufferAllocationOptions, ConcurrentOdbcReader, Error};
+use crate::{
+    BufferAllocationOptions, ColumnNameOptions, ConcurrentOdbcReader, Error, OnInvalidDateTime,
+    OutOfRangeTimestamp,
+};
 
-use super::{to_record_batch::ToRecordBatch, Quirks};
+use super::{
+    cancel::{CancelHandle, Cancellation, StatementGuard},
+    rejects::{rejects_to_record_batch, OnConversionError, Reject},
+    stats::{BatchStats, ProgressCallback, ReaderStats, StatsTracker},
+    to_record_batch::ToRecordBatch,
+    transit_buffer::TransitBuffer,
+    DateTimeOptions, Quirks, StructFormat,
+};
 
 /// Arrow ODBC reader. Implements the [`arrow::record_batch::RecordBatchReader`] trait so it can be
 /// used to fill Arrow arrays from an ODBC data source.
@@ -63,12 +79,29 @@ use super::{to_record_batch::ToRecordBatch, Quirks};
 pub struct OdbcReader<C: Cursor> {
     /// Converts the content of ODBC buffers into Arrow record batches
     converter: ToRecordBatch,
+    /// Allows cancelling the read from other threads.
+    cancellation: Cancellation,
+    /// Keeps cancel handles from using the statement after it has been freed. Declared before
+    /// `batch_stream` so it is dropped before the statement handle is freed.
+    statement_guard: StatementGuard,
     /// Fetches values from the ODBC datasource using columnar batches. Values are streamed batch
     /// by batch in order to avoid reallocation of the buffers used for tranistion.
-    batch_stream: BlockCursor<C, ColumnarAnyBuffer>,
+    batch_stream: BlockCursor<C, TransitBuffer>,
     /// We remember if the user decided to use fallibale allocations or not in case we need to
     /// allocate another buffer due to a state transition towards [`ConcurrentOdbcReader`].
     fallibale_allocations: bool,
+    /// Number of batches the fetch thread may fill ahead of the application, should this reader
+    /// be turned into a [`ConcurrentOdbcReader`].
+    prefetch_depth: usize,
+    /// Convert batches into Arrow arrays in a dedicated system thread, should this reader be turned
+    /// into a [`ConcurrentOdbcReader`].
+    background_conversion: bool,
+    /// Number of rows we are still allowed to emit. `None` means there is no limit.
+    remaining_rows: Option<usize>,
+    /// Fetch and conversion statistics
+    stats: StatsTracker,
+    /// Values which could not be converted, recorded since the last call to `take_rejects`.
+    rejects: Vec<Reject>,
 }
 
 impl<C: Cursor> OdbcReader<C> {
@@ -77,7 +110,8 @@ impl<C: Cursor> OdbcReader<C> {
     /// Steals all resources from this [`OdbcReader`] instance, and allocates another buffer for
     /// transiting data from the ODBC data source to the application. This way one buffer can be
     /// written to by a dedicated system thread, while the other is read by the application. Use
-    /// this if you want to trade memory for speed.
+    /// this if you want to trade memory for speed. If a prefetch depth larger than one has been
+    /// set using [`OdbcReaderBuilder::with_prefetch_depth`], even more buffers are allocated.
     ///
     /// # Example
     ///
@@ -135,7 +169,14 @@ impl<C: Cursor> OdbcReader<C> {
         ConcurrentOdbcReader::from_block_cursor(
             self.batch_stream,
             self.converter,
+            self.cancellation,
+            self.statement_guard,
             self.fallibale_allocations,
+            self.prefetch_depth,
+            self.background_conversion,
+            self.remaining_rows,
+            self.stats,
+            self.rejects,
         )
     }
 
@@ -144,7 +185,10 @@ impl<C: Cursor> OdbcReader<C> {
     /// One application of this is to process more than one result set in case you executed a stored
     /// procedure.
     pub fn into_cursor(self) -> Result<C, odbc_api::Error> {
-        let (cursor, _buffer) = self.batch_stream.unbind()?;
+        let batch_stream = self.batch_stream;
+        let (cursor, _buffer) = self
+            .statement_guard
+            .free_on_error(|| batch_stream.unbind())?;
         Ok(cursor)
     }
 
@@ -154,6 +198,47 @@ impl<C: Cursor> OdbcReader<C> {
     pub fn max_rows_per_batch(&self) -> usize {
         self.batch_stream.row_array_size()
     }
+
+    /// A handle which can be send to other threads in order to cancel the read. After cancelling,
+    /// the reader emits a [`crate::Cancelled`] error and ends the iteration. The handle is carried
+    /// over if the reader is turned into a [`ConcurrentOdbcReader`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use arrow_odbc::{odbc_api::Cursor, OdbcReaderBuilder};
+    /// use std::thread;
+    ///
+    /// fn read_until_stopped(cursor: impl Cursor) -> Result<(), anyhow::Error> {
+    ///     let reader = OdbcReaderBuilder::new().build(cursor)?;
+    ///     let cancel_handle = reader.cancel_handle();
+    ///     // E.g. triggered by a "stop query" button
+    ///     thread::spawn(move || cancel_handle.cancel().unwrap());
+    ///     for batch in reader {
+    ///         // Last item is an error, if cancelled in time
+    ///         let batch = batch?;
+    ///         // ... process batch ...
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancellation.handle()
+    }
+
+    /// Statistics accumulated over all batches fetched so far. Useful to find out whether time is
+    /// spent waiting for the data source or converting the data into Arrow arrays.
+    pub fn stats(&self) -> &ReaderStats {
+        self.stats.cumulative()
+    }
+
+    /// Values which could not be converted into their Arrow target type since the last call to
+    /// this method, as a record batch described by [`crate::rejects_schema`]. Only ever contains
+    /// rows, if the reader has been configured with [`OnConversionError::NullAndRecord`] or
+    /// [`OnConversionError::DropAndRecord`].
+    pub fn take_rejects(&mut self) -> RecordBatch {
+        rejects_to_record_batch(take(&mut self.rejects))
+    }
 }
 
 impl<C> Iterator for OdbcReader<C>
@@ -163,18 +248,44 @@ where
     type Item = Result<RecordBatch, ArrowError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // Do not fetch any more batches, if we already emitted all the rows the user asked for.
+        if self.remaining_rows == Some(0) {
+            return None;
+        }
+        if self.cancellation.is_cancelled() {
+            return self.cancellation.report();
+        }
+        let start_fetch = Instant::now();
         match self.batch_stream.fetch_with_truncation_check(true) {
             // We successfully fetched a batch from the database. Try to copy it into a record batch
             // and forward errors if any.
             Ok(Some(batch)) => {
-                let result_record_batch = self
-                    .converter
-                    .buffer_to_record_batch(batch)
-                    .map_err(|mapping_error| ArrowError::ExternalError(Box::new(mapping_error)));
+                let fetch_duration = start_fetch.elapsed();
+                let mut conversion_durations = Vec::new();
+                let result_record_batch = self.converter.buffer_to_record_batch(
+                    batch,
+                    self.stats.cumulative().num_rows,
+                    &mut conversion_durations,
+                    &mut self.rejects,
+                );
+                self.stats.record(
+                    batch.num_rows(),
+                    batch.transferred_bytes(),
+                    fetch_duration,
+                    conversion_durations,
+                );
+                let result_record_batch = result_record_batch
+                    .map(|batch| limit_rows(batch, &mut self.remaining_rows))
+                    .map_err(|conversion_error| {
+                        ArrowError::ExternalError(Box::new(conversion_error))
+                    });
                 Some(result_record_batch)
             }
             // We ran out of batches in the result set. End the iterator.
             Ok(None) => None,
+            // The error is likely caused by the fetch being cancelled. Report the cancellation
+            // rather than the error the driver chose for it.
+            Err(_) if self.cancellation.is_cancelled() => self.cancellation.report(),
             // We had an error fetching the next batch from the database, let's report it as an
             // external error.
             Err(odbc_error) => Some(Err(ArrowError::ExternalError(Box::new(odbc_error)))),
@@ -203,11 +314,20 @@ pub struct OdbcReaderBuilder {
     /// for the user.
     max_num_rows_per_batch: usize,
     max_bytes_per_batch: usize,
+    max_bytes_in_transit: Option<usize>,
+    max_total_rows: Option<usize>,
+    prefetch_depth: usize,
+    background_conversion: bool,
     schema: Option<SchemaRef>,
+    column_name_options: ColumnNameOptions,
     max_text_size: Option<usize>,
     max_binary_size: Option<usize>,
     fallibale_allocations: bool,
     quirks: Quirks,
+    on_conversion_error: OnConversionError,
+    date_time_options: DateTimeOptions,
+    progress: Option<ProgressCallback>,
+    struct_formats: HashMap<String, StructFormat>,
 }
 
 impl OdbcReaderBuilder {
@@ -224,11 +344,20 @@ impl OdbcReaderBuilder {
         OdbcReaderBuilder {
             max_num_rows_per_batch: DEFAULT_MAX_ROWS_PER_BATCH,
             max_bytes_per_batch: DEFAULT_MAX_BYTES_PER_BATCH,
+            max_bytes_in_transit: None,
+            max_total_rows: None,
+            prefetch_depth: 1,
+            background_conversion: false,
             schema: None,
+            column_name_options: ColumnNameOptions::default(),
             max_text_size: None,
             max_binary_size: None,
             fallibale_allocations: false,
             quirks: Quirks::new(),
+            on_conversion_error: OnConversionError::Fail,
+            date_time_options: DateTimeOptions::default(),
+            progress: None,
+            struct_formats: HashMap::new(),
         }
     }
 
@@ -253,6 +382,55 @@ impl OdbcReaderBuilder {
         self
     }
 
+    /// Number of batches the fetch thread of a [`ConcurrentOdbcReader`] may fill ahead of the
+    /// application. Each of them requires its own transit buffer, so the concurrent reader holds
+    /// `prefetch_depth + 1` transit buffers in total. A deeper pipeline can smooth out throughput,
+    /// if the time it takes to fetch a batch varies a lot, e.g. due to a high latency link. Use
+    /// [`Self::with_max_bytes_in_transit`] to limit the memory used by all these buffers combined.
+    /// Values smaller than `1` are treated as `1`. This is also the default. Has no effect, unless
+    /// the reader is turned into a [`ConcurrentOdbcReader`] using [`OdbcReader::into_concurrent`].
+    pub fn with_prefetch_depth(&mut self, prefetch_depth: usize) -> &mut Self {
+        self.prefetch_depth = max(prefetch_depth, 1);
+        self
+    }
+
+    /// If `true` the [`ConcurrentOdbcReader`] does not only fetch batches in a dedicated system
+    /// thread, but also converts them into Arrow arrays in another one. The application thread then
+    /// only receives finished record batches. Up to prefetch depth record batches are held ahead of
+    /// the application. Consider this if converting the batches takes a significant amount of time,
+    /// e.g. for wide tables with many text columns. Has no effect, unless the reader is turned into
+    /// a [`ConcurrentOdbcReader`] using [`OdbcReader::into_concurrent`]. Default is `false`.
+    pub fn with_background_conversion(&mut self, background_conversion: bool) -> &mut Self {
+        self.background_conversion = background_conversion;
+        self
+    }
+
+    /// An upper bound in bytes for all transit buffers combined. The limit for each individual
+    /// buffer is derived by dividing this by the number of transit buffers used by a
+    /// [`ConcurrentOdbcReader`], i.e. prefetch depth + 1. This limit is applied in addition to
+    /// [`Self::with_max_bytes_per_batch`]. Whichever leads to a smaller buffer is used. Since the
+    /// builder can not know wether the reader will be turned into a concurrent one, the budget is
+    /// divided in the same way for a sequential reader. By default there is no such limit.
+    pub fn with_max_bytes_in_transit(&mut self, max_bytes_in_transit: usize) -> &mut Self {
+        self.max_bytes_in_transit = Some(max_bytes_in_transit);
+        self
+    }
+
+    /// Limits the total number of rows emitted by the reader. The reader stops after exactly
+    /// `max_total_rows` rows (or less, if the result set is smaller). The last record batch is
+    /// sliced if need be. Useful e.g. for previewing a table. The transit buffer is never allocated
+    /// larger than required to hold `max_total_rows`. Note that this limit is applied on the client
+    /// side, the database may still have to process the entire query. Use `TOP`, `LIMIT` or
+    /// `FETCH FIRST` in your SQL statement to limit the rows on the server side. The builder does not
+    /// pass the limit on as `SQL_ATTR_MAX_ROWS` either. Data sources apply that attribute then
+    /// executing the statement, but the builder only receives the cursor afterwards. The limit also
+    /// applies to the [`ConcurrentOdbcReader`] created via [`OdbcReader::into_concurrent`]. By
+    /// default there is no limit.
+    pub fn with_max_total_rows(&mut self, max_total_rows: usize) -> &mut Self {
+        self.max_total_rows = Some(max_total_rows);
+        self
+    }
+
     /// Describes the types of the Arrow Arrays in the record batches. It is also used to determine
     /// CData type requested from the data source. If this is not explicitly set the type is infered
     /// from the schema information provided by the ODBC driver. A reason for setting this
@@ -263,11 +441,31 @@ impl OdbcReaderBuilder {
     /// struggle with reporting nullability correctly and just report every column as nullable.
     /// Explicitly specifying a schema can also compensate for such shortcomings if it turns out to
     /// be relevant.
+    ///
+    /// A `Struct` field is assembled from one column for each of its leaves, starting at the
+    /// position of the field. The positions of subsequent fields shift accordingly. Children are
+    /// matched to columns named after their path (e.g. `address.street`), or else by position.
+    /// Children of nullable structs are read as nullable and a row whose children are all `NULL`
+    /// is a `NULL` struct. With the `json` feature, structs whose leaves are all booleans,
+    /// integers, floats or strings could be parsed from a single column of JSON text instead. For
+    /// these the format must be chosen using [`Self::with_struct_format`].
     pub fn with_schema(&mut self, schema: SchemaRef) -> &mut Self {
         self.schema = Some(schema);
         self
     }
 
+    /// Controls how the names of the fields are derived from the column names reported by the ODBC
+    /// driver, then inferring the schema. Use this e.g. to guarantee unique field names for queries
+    /// like `SELECT a.id, b.id FROM a, b`. Has no effect if the schema is set explicitly using
+    /// [`Self::with_schema`]. By default the names reported by the driver are used as is.
+    pub fn with_column_name_options(
+        &mut self,
+        column_name_options: ColumnNameOptions,
+    ) -> &mut Self {
+        self.column_name_options = column_name_options;
+        self
+    }
+
     /// An upper limit for the size of buffers bound to variadic text columns of the data source.
     /// This limit does not (directly) apply to the size of the created arrow buffers, but rather
     /// applies to the buffers used for the data in transit. Use this option if you have e.g.
@@ -318,21 +516,114 @@ impl OdbcReaderBuilder {
         self
     }
 
+    /// Controls what happens if a value can not be converted into its Arrow target type, e.g. a
+    /// timestamp which is out of range for nanoseconds precision. By default
+    /// ([`OnConversionError::Fail`]) the reader emits an error instead of the batch. Ingestion
+    /// pipelines may prefer to keep going, replacing the value with `NULL` or dropping the row.
+    /// Such values are recorded and can be drained using [`OdbcReader::take_rejects`].
+    pub fn with_on_conversion_error(
+        &mut self,
+        on_conversion_error: OnConversionError,
+    ) -> &mut Self {
+        self.on_conversion_error = on_conversion_error;
+        self
+    }
+
+    /// Controls what happens if the driver returns a date or timestamp which does not exist, e.g.
+    /// MySQL's zero date `0000-00-00` or a leap second represented with `60` seconds. By default
+    /// ([`OnInvalidDateTime::Fail`]) such values are treated like any other value which can not be
+    /// converted, see [`Self::with_on_conversion_error`]. Alternatively they can be replaced with
+    /// `NULL` or a sentinel.
+    pub fn with_on_invalid_date_time(
+        &mut self,
+        on_invalid_date_time: OnInvalidDateTime,
+    ) -> &mut Self {
+        self.date_time_options.on_invalid_date_time = on_invalid_date_time;
+        self
+    }
+
+    /// Controls what happens if the driver returns a timestamp which is outside the range
+    /// representable with nanoseconds precision (1677-09-21 00:12:44 to 2262-04-11
+    /// 23:47:16.854775807). By default ([`OutOfRangeTimestamp::Fail`]) such values are treated
+    /// like any other value which can not be converted, see [`Self::with_on_conversion_error`].
+    /// Alternatively they can be saturated, replaced with `NULL`, or columns can be inferred with
+    /// microseconds precision in the first place. The latter explicitly truncates the seventh and
+    /// further fractional digits of every value in these columns, not only of the ones out of
+    /// range.
+    pub fn with_out_of_range_timestamp(
+        &mut self,
+        out_of_range_timestamp: OutOfRangeTimestamp,
+    ) -> &mut Self {
+        self.date_time_options.out_of_range_timestamp = out_of_range_timestamp;
+        self
+    }
+
+    /// Chooses whether the `Struct` field named `field_name` in the schema set with
+    /// [`Self::with_schema`] is parsed from JSON text or assembled from one column for each of its
+    /// leaves. Structs which could be read either way, fail to build the reader unless their
+    /// format is chosen explicitly. Structs with leaves which can not be parsed from JSON text are
+    /// assembled from columns by default, as are all structs without the `json` feature.
+    pub fn with_struct_format(
+        &mut self,
+        field_name: impl Into<String>,
+        format: StructFormat,
+    ) -> &mut Self {
+        self.struct_formats.insert(field_name.into(), format);
+        self
+    }
+
+    /// Registers a callback which is invoked with the statistics of each batch, after it has been
+    /// fetched and converted. Use this to report progress or to find out where time is spent. The
+    /// callback is invoked on the thread consuming the reader.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use arrow_odbc::OdbcReaderBuilder;
+    ///
+    /// let mut builder = OdbcReaderBuilder::new();
+    /// builder.with_progress(|stats| {
+    ///     println!("Fetched {} rows in {:?}", stats.num_rows, stats.fetch_duration)
+    /// });
+    /// ```
+    pub fn with_progress(
+        &mut self,
+        progress: impl Fn(&BatchStats) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
     /// No matter if the user explicitly specified a limit in row size, a memory limit, both or
     /// neither. In order to construct a reader we need to decide on the buffer size in rows.
     fn buffer_size_in_rows(&self, bytes_per_row: usize) -> Result<usize, Error> {
+        // The memory budget for all transit buffers is shared equally between them.
+        let max_bytes_per_batch = if let Some(max_bytes_in_transit) = self.max_bytes_in_transit {
+            min(
+                self.max_bytes_per_batch,
+                max_bytes_in_transit / (self.prefetch_depth + 1),
+            )
+        } else {
+            self.max_bytes_per_batch
+        };
         // If schema is empty, return before division by zero error.
         if bytes_per_row == 0 {
-            return Ok(self.max_bytes_per_batch);
+            return Ok(max_bytes_per_batch);
         }
-        let rows_per_batch = self.max_bytes_per_batch / bytes_per_row;
+        let rows_per_batch = max_bytes_per_batch / bytes_per_row;
         if rows_per_batch == 0 {
-            Err(Error::OdbcBufferTooSmall {
-                max_bytes_per_batch: self.max_bytes_per_batch,
+            return Err(Error::OdbcBufferTooSmall {
+                max_bytes_per_batch,
                 bytes_per_row,
-            })
+            });
+        }
+        let rows_per_batch = min(self.max_num_rows_per_batch, rows_per_batch);
+        // No need to allocate more rows than we are going to emit in total. We still need to be
+        // able to hold at least one row though.
+        if let Some(max_total_rows) = self.max_total_rows {
+            Ok(min(max(max_total_rows, 1), rows_per_batch))
         } else {
-            Ok(min(self.max_num_rows_per_batch, rows_per_batch))
+            Ok(rows_per_batch)
         }
     }
 
@@ -358,19 +649,48 @@ impl OdbcReaderBuilder {
         let converter = ToRecordBatch::new(
             &mut cursor,
             self.schema.clone(),
+            self.column_name_options,
             buffer_allocation_options,
             &self.quirks,
+            self.on_conversion_error,
+            self.date_time_options,
+            &self.struct_formats,
         )?;
         let bytes_per_row = converter.row_size_in_bytes();
         let buffer_size_in_rows = self.buffer_size_in_rows(bytes_per_row)?;
         let row_set_buffer =
             converter.allocate_buffer(buffer_size_in_rows, self.fallibale_allocations)?;
+        let stats = StatsTracker::new(converter.num_columns(), self.progress.clone());
+        let (cancellation, statement_guard) = Cancellation::new(cursor.as_stmt_ref().as_sys());
         let batch_stream = cursor.bind_buffer(row_set_buffer).unwrap();
 
         Ok(OdbcReader {
             converter,
+            cancellation,
+            statement_guard,
             batch_stream,
             fallibale_allocations: self.fallibale_allocations,
+            prefetch_depth: self.prefetch_depth,
+            background_conversion: self.background_conversion,
+            remaining_rows: self.max_total_rows,
+            stats,
+            rejects: Vec::new(),
         })
     }
 }
+
+/// Slices `batch` so it does not contain more than `remaining_rows` and subtracts the number of
+/// rows in the result from `remaining_rows`. Batches are passed through unchanged if
+/// `remaining_rows` is `None`.
+pub(crate) fn limit_rows(batch: RecordBatch, remaining_rows: &mut Option<usize>) -> RecordBatch {
+    let Some(remaining) = remaining_rows else {
+        return batch;
+    };
+    let batch = if batch.num_rows() > *remaining {
+        batch.slice(0, *remaining)
+    } else {
+        batch
+    };
+    *remaining -= batch.num_rows();
+    batch
+}
@@ -0,0 +1,238 @@
+use std::{
+    sync::mpsc::{sync_channel, Receiver},
+    thread,
+};
+
+use arrow::{
+    datatypes::SchemaRef,
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+use odbc_api::{Connection, CursorImpl, StatementConnection};
+
+use crate::Error;
+
+use super::{OdbcReader, OdbcReaderBuilder};
+
+/// Cursor type of the readers created by [`PartitionedReader`]. Each partition owns its
+/// connection.
+pub type PartitionCursor = CursorImpl<StatementConnection<'static>>;
+
+/// Describes how the result set of the base query is split into partitions. See
+/// [`PartitionedReader::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionSpec {
+    /// Split the values of an integer key column into `num_partitions` contiguous ranges of
+    /// (roughly) equal width between `min` and `max`. The first range is open to the bottom and
+    /// also includes `NULL`, the last one is open to the top. So no row is lost, even if `min` and
+    /// `max` turn out to be no strict bounds. A single partition holds all rows.
+    Range {
+        /// Name of the key column. It is inserted into the generated SQL as is, so quote it if
+        /// required by your database.
+        column: String,
+        min: i64,
+        max: i64,
+        /// Must be at least `1`.
+        num_partitions: usize,
+    },
+    /// One partition for each SQL predicate. The predicates are used as is in a `WHERE` clause. It
+    /// is up to the caller to ensure they are disjunct and cover all rows of interest.
+    Predicates(Vec<String>),
+}
+
+impl PartitionSpec {
+    /// One SQL predicate for each partition. Empty if `num_partitions` is `0`.
+    pub fn predicates(&self) -> Vec<String> {
+        match self {
+            PartitionSpec::Range {
+                column,
+                min,
+                max,
+                num_partitions,
+            } => range_predicates(column, *min, *max, *num_partitions),
+            PartitionSpec::Predicates(predicates) => predicates.clone(),
+        }
+    }
+}
+
+fn range_predicates(column: &str, min: i64, max: i64, num_partitions: usize) -> Vec<String> {
+    if num_partitions == 0 {
+        return Vec::new();
+    }
+    if num_partitions == 1 {
+        return vec!["1=1".to_owned()];
+    }
+    // Calculate in i128 so neither the width, nor the bounds can overflow.
+    let (min, max) = (min as i128, max.max(min) as i128);
+    let num = num_partitions as i128;
+    let width = ((max - min + 1) + num - 1) / num;
+    (0..num)
+        .map(|index| {
+            let lower = min + index * width;
+            let upper = lower + width;
+            if index == 0 {
+                format!("({column} < {upper} OR {column} IS NULL)")
+            } else if index == num - 1 {
+                format!("{column} >= {lower}")
+            } else {
+                format!("{column} >= {lower} AND {column} < {upper}")
+            }
+        })
+        .collect()
+}
+
+/// Extracts a result set over several connections in parallel. The base query is executed once for
+/// each partition, restricted by the predicate of the partition. Each partition is read by its own
+/// [`OdbcReader`]. All readers share the schema inferred from the first partition.
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{
+///     odbc_api::{ConnectionOptions, Environment},
+///     OdbcReaderBuilder, PartitionSpec, PartitionedReader,
+/// };
+/// use std::sync::OnceLock;
+///
+/// static ENV: OnceLock<Environment> = OnceLock::new();
+///
+/// fn main() -> Result<(), anyhow::Error> {
+///     let env = ENV.get_or_init(|| Environment::new().unwrap());
+///     let connect = || {
+///         env.connect_with_connection_string("DSN=MyDatabase;", ConnectionOptions::default())
+///     };
+///     let partitions = PartitionSpec::Range {
+///         column: "id".to_owned(),
+///         min: 0,
+///         max: 2_000_000_000,
+///         num_partitions: 8,
+///     };
+///     let reader = PartitionedReader::new(
+///         connect,
+///         "SELECT * FROM MyTable",
+///         &partitions,
+///         &OdbcReaderBuilder::new(),
+///     )?;
+///     for batch in reader.into_merged() {
+///         // ... process batch ...
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct PartitionedReader {
+    schema: SchemaRef,
+    readers: Vec<OdbcReader<PartitionCursor>>,
+}
+
+impl PartitionedReader {
+    /// Opens one connection using `connect` for each partition and executes the partitioned queries
+    /// in parallel. The partitioned query is `SELECT * FROM ({base_query}) partitioned_query WHERE
+    /// {predicate}`, so `base_query` must be valid as a subquery for your database.
+    ///
+    /// `reader_builder` is used to configure each of the readers. If it does not specify a schema,
+    /// the schema is inferred from the first partition and used for all the others.
+    ///
+    /// Fails with [`Error::NoPartitions`] if `partitions` does not describe at least one partition.
+    pub fn new(
+        connect: impl Fn() -> Result<Connection<'static>, odbc_api::Error> + Sync,
+        base_query: &str,
+        partitions: &PartitionSpec,
+        reader_builder: &OdbcReaderBuilder,
+    ) -> Result<Self, Error> {
+        let predicates = partitions.predicates();
+        if predicates.is_empty() {
+            return Err(Error::NoPartitions);
+        }
+        // Connecting and executing the queries likely takes up most of the time, so we do it in
+        // parallel.
+        let cursors = thread::scope(|scope| {
+            let connect = &connect;
+            let threads: Vec<_> = predicates
+                .iter()
+                .enumerate()
+                .map(|(partition, predicate)| {
+                    let query =
+                        format!("SELECT * FROM ({base_query}) partitioned_query WHERE {predicate}");
+                    scope.spawn(move || {
+                        let connection = connect()
+                            .map_err(|source| Error::PartitionFailed { partition, source })?;
+                        connection
+                            .into_cursor(&query, ())
+                            .map_err(|source| Error::PartitionFailed { partition, source })?
+                            .ok_or(Error::PartitionWithoutResultSet { partition })
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let mut cursors = cursors.into_iter();
+        let first = reader_builder.build(cursors.next().unwrap())?;
+        let schema = first.schema();
+        let mut builder = reader_builder.clone();
+        builder.with_schema(schema.clone());
+        let mut readers = vec![first];
+        for cursor in cursors {
+            readers.push(builder.build(cursor)?);
+        }
+        Ok(Self { schema, readers })
+    }
+
+    /// Schema shared by all partitions.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// One reader for each partition, in the order of the partition predicates.
+    pub fn into_readers(self) -> Vec<OdbcReader<PartitionCursor>> {
+        self.readers
+    }
+
+    /// Reads all partitions concurrently, each in a dedicated system thread, and emits their
+    /// batches as one stream. The batches of different partitions are interleaved in no particular
+    /// order. Rejects recorded by the individual readers are not accessible this way, use
+    /// [`Self::into_readers`] instead, if you need them.
+    pub fn into_merged(self) -> MergedReader {
+        let (send_batch, receive_batch) = sync_channel(self.readers.len());
+        for reader in self.readers {
+            let send_batch = send_batch.clone();
+            thread::spawn(move || {
+                for batch in reader {
+                    if send_batch.send(batch).is_err() {
+                        // The application stopped receiving batches. So we stop reading them.
+                        break;
+                    }
+                }
+            });
+        }
+        MergedReader {
+            schema: self.schema,
+            receive_batch,
+        }
+    }
+}
+
+/// Record batches of all partitions of a [`PartitionedReader`] as one unordered stream. Created by
+/// [`PartitionedReader::into_merged`].
+pub struct MergedReader {
+    schema: SchemaRef,
+    /// Once every partition is consumed, all senders are dropped and receiving ends the iteration.
+    receive_batch: Receiver<Result<RecordBatch, ArrowError>>,
+}
+
+impl Iterator for MergedReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receive_batch.recv().ok()
+    }
+}
+
+impl RecordBatchReader for MergedReader {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    mem::swap,
+    sync::mpsc::{sync_channel, Receiver, SyncSender},
+    thread::{self, JoinHandle},
+};
+
+use odbc_api::{BlockCursor, Cursor};
+
+use super::{
+    cancel::{CancelHandle, StatementGuard},
+    transit_buffer::TransitBuffer,
+};
+
+/// Fetches batches in a dedicated system thread, like [`odbc_api::ConcurrentBlockCursor`]. Yet in
+/// addition to the buffer bound to the cursor, the fetch thread may fill up to `depth - 1` more
+/// buffers ahead of the application. This allows to smooth out throughput if the time to fetch a
+/// batch varies a lot.
+///
+/// We can not use [`odbc_api::ConcurrentBlockCursor`] itself, because its channels hold only a
+/// single batch and it only accepts a [`odbc_api::buffers::ColumnarAnyBuffer`]. We need to fetch
+/// into a [`TransitBuffer`], which binds some columns as `SQL_NUMERIC_STRUCT` or
+/// `SQL_INTERVAL_STRUCT`.
+///
+/// Dropping it cancels a fetch which may be in progress and joins the fetch thread, so the cursor is
+/// not used after the reader is gone.
+pub struct PrefetchBlockCursor<C> {
+    /// Used to send the buffers back to the fetch thread after their contents have been copied
+    /// into Arrow arrays. Can hold all buffers, so sending never blocks.
+    send_buffer: SyncSender<TransitBuffer>,
+    /// Receives filled batches from the fetch thread. Once the source is empty or if an error
+    /// occurs its associated sender is dropped, and receiving batches will return an error (which
+    /// we expect during normal operation and cleanup, and is not forwarded to the user).
+    receive_batch: Receiver<TransitBuffer>,
+    /// We join with the fetch thread if we stop receiving batches or `into_cursor` is called.
+    /// `None` if the thread has already been joined.
+    fetch_thread: Option<JoinHandle<Result<C, odbc_api::Error>>>,
+    /// Only `Some`, if the cursor has been consumed succesfully and `fetch_thread` has been joined.
+    cursor: Option<C>,
+    /// Cancels a fetch in progress, so we do not wait for it to finish then dropped.
+    cancel_handle: CancelHandle,
+}
+
+impl<C> PrefetchBlockCursor<C>
+where
+    C: Cursor + Send + 'static,
+{
+    /// Starts fetching immediately into the buffer bound to `block_cursor`. Use [`Self::fill`] to
+    /// supply the fetch thread with up to `depth - 1` additional buffers. `statement_guard` is moved
+    /// to the fetch thread, since it owns the statement from now on.
+    pub fn new(
+        block_cursor: BlockCursor<C, TransitBuffer>,
+        statement_guard: StatementGuard,
+        depth: usize,
+    ) -> Self {
+        // Enough capacity to hold every buffer, besides the one bound to the cursor. This way
+        // neither the application, nor the fetch thread ever block sending buffers back.
+        let (send_buffer, receive_buffer) = sync_channel(depth);
+        // At most `depth` batches are filled ahead of the application.
+        let (send_batch, receive_batch) = sync_channel(depth);
+
+        let cancel_handle = statement_guard.handle();
+        let fetch_thread = thread::spawn(move || {
+            let mut block_cursor = block_cursor;
+            // Declared after the block cursor, so the statement is removed from the cancel handles
+            // before it is freed on every path, including errors and panics.
+            let statement_guard = statement_guard;
+            loop {
+                match block_cursor.fetch_with_truncation_check(true) {
+                    Ok(Some(_batch)) => (),
+                    Ok(None) => {
+                        break statement_guard
+                            .free_on_error(|| block_cursor.unbind())
+                            .map(|(undbound_cursor, _buffer)| undbound_cursor);
+                    }
+                    Err(odbc_error) => {
+                        drop(send_batch);
+                        break Err(odbc_error);
+                    }
+                }
+                let (cursor, buffer) = statement_guard.free_on_error(|| block_cursor.unbind())?;
+                if send_batch.send(buffer).is_err() {
+                    // The application stopped receiving batches. So we stop fetching them.
+                    break Ok(cursor);
+                }
+                // Wait for a buffer to fill. Does not block if there are spare buffers.
+                match receive_buffer.recv() {
+                    Err(_) => break Ok(cursor),
+                    Ok(next_buffer) => {
+                        block_cursor = statement_guard
+                            .free_on_error(|| cursor.bind_buffer(next_buffer))
+                            .unwrap();
+                    }
+                }
+            }
+        });
+
+        Self {
+            send_buffer,
+            receive_batch,
+            fetch_thread: Some(fetch_thread),
+            cursor: None,
+            cancel_handle,
+        }
+    }
+
+    /// Join fetch thread and yield the cursor back.
+    pub fn into_cursor(mut self) -> Result<C, odbc_api::Error> {
+        self.hang_up();
+        if let Some(cursor) = self.cursor.take() {
+            Ok(cursor)
+        } else {
+            self.fetch_thread.take().unwrap().join().unwrap()
+        }
+    }
+}
+
+impl<C> PrefetchBlockCursor<C> {
+    /// Replaces our ends of the channels with disconnected ones. Any send or receive the fetch
+    /// thread is blocked on fails, so it stops fetching and returns.
+    fn hang_up(&mut self) {
+        let (send_buffer, _) = sync_channel(0);
+        let (_, receive_batch) = sync_channel(0);
+        self.send_buffer = send_buffer;
+        self.receive_batch = receive_batch;
+    }
+
+    /// Receive the next filled buffer. `None` if the cursor is consumed. Blocks until a batch is
+    /// available.
+    pub fn fetch(&mut self) -> Result<Option<TransitBuffer>, odbc_api::Error> {
+        match self.receive_batch.recv() {
+            Ok(batch) => Ok(Some(batch)),
+            // Fetch thread stopped sending batches. Either because we consumed the result set
+            // completly or we hit an error.
+            Err(_receive_error) => {
+                if let Some(join_handle) = self.fetch_thread.take() {
+                    self.cursor = Some(join_handle.join().unwrap()?);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// A handle to cancel the statement the fetch thread fetches from.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel_handle.clone()
+    }
+
+    /// Hand a buffer to the fetch thread in order for it to be filled.
+    pub fn fill(&mut self, buffer: TransitBuffer) {
+        let _ = self.send_buffer.send(buffer);
+    }
+
+    /// Fetches the next batch into `buffer` and hands the buffer previously referenced by `buffer`
+    /// back to the fetch thread.
+    pub fn fetch_into(&mut self, buffer: &mut TransitBuffer) -> Result<bool, odbc_api::Error> {
+        if let Some(mut batch) = self.fetch()? {
+            swap(buffer, &mut batch);
+            self.fill(batch);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl<C> Drop for PrefetchBlockCursor<C> {
+    fn drop(&mut self) {
+        self.hang_up();
+        if let Some(fetch_thread) = self.fetch_thread.take() {
+            // Errors have no one left to be reported to. Panics would only cause a double panic.
+            // Do not wait for a fetch in progress to finish.
+            let _ = self.cancel_handle.cancel();
+            let _ = fetch_thread.join();
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, StringArray, UInt32Array, UInt64Array},
+    datatypes::{DataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+
+use super::MappingError;
+
+/// Controls how a reader reacts to values which can not be converted into their Arrow target type,
+/// e.g. a timestamp which is out of range for nanoseconds precision. See
+/// [`crate::OdbcReaderBuilder::with_on_conversion_error`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnConversionError {
+    /// Emit a [`crate::ConversionError`] instead of the record batch. This is the default.
+    #[default]
+    Fail,
+    /// Replace the value with `NULL` and record it as a reject. Since `NULL` is not an option for
+    /// non nullable fields, rows with rejected values in such fields are dropped from the batch.
+    NullAndRecord,
+    /// Drop the entire row from the batch and record the value as a reject.
+    DropAndRecord,
+}
+
+/// A value which could not be converted into its Arrow target type. Recorded then reading with
+/// [`OnConversionError::NullAndRecord`] or [`OnConversionError::DropAndRecord`].
+#[derive(Debug)]
+pub struct Reject {
+    /// Zero based index of the row, counting from the start of the cursor.
+    pub row_number: usize,
+    /// Zero based index of the column.
+    pub column_index: usize,
+    /// Name of the field in the Arrow schema.
+    pub column_name: String,
+    /// Value as fetched from the ODBC buffer, rendered as text. Dates and timestamps are rendered
+    /// like `2023-02-30 25:00:00.000000000`, numeric structs as decimal text with the scale chosen
+    /// by the driver, interval structs as SQL interval literals like `1-02` or `3 04:05:06.0`, and
+    /// text as is.
+    pub raw_value: String,
+    pub error: MappingError,
+}
+
+/// Schema of the record batches returned by [`crate::OdbcReader::take_rejects`].
+///
+/// | Name           | Type   |
+/// | -------------- | ------ |
+/// | `row_number`   | UInt64 |
+/// | `column_index` | UInt32 |
+/// | `column_name`  | Utf8   |
+/// | `raw_value`    | Utf8   |
+/// | `error`        | Utf8   |
+pub fn rejects_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("row_number", DataType::UInt64, false),
+        Field::new("column_index", DataType::UInt32, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("raw_value", DataType::Utf8, false),
+        Field::new("error", DataType::Utf8, false),
+    ])
+}
+
+/// Converts recorded rejects into a record batch described by [`rejects_schema`].
+pub fn rejects_to_record_batch(rejects: Vec<Reject>) -> RecordBatch {
+    let row_numbers = UInt64Array::from_iter_values(rejects.iter().map(|r| r.row_number as u64));
+    let column_indices =
+        UInt32Array::from_iter_values(rejects.iter().map(|r| r.column_index as u32));
+    let column_names = StringArray::from_iter_values(rejects.iter().map(|r| &r.column_name));
+    let raw_values = StringArray::from_iter_values(rejects.iter().map(|r| &r.raw_value));
+    let errors = StringArray::from_iter_values(rejects.iter().map(|r| r.error.to_string()));
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(row_numbers),
+        Arc::new(column_indices),
+        Arc::new(column_names),
+        Arc::new(raw_values),
+        Arc::new(errors),
+    ];
+    RecordBatch::try_new(Arc::new(rejects_schema()), columns).unwrap()
+}
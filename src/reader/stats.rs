@@ -0,0 +1,112 @@
+use std::{sync::Arc, time::Duration};
+
+/// Statistics about fetching and converting a single record batch. Passed to the callback
+/// registered with [`crate::OdbcReaderBuilder::with_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchStats {
+    /// Number of rows in the batch fetched from the data source. This may be more than the number
+    /// of rows in the emitted record batch, if a limit for the total number of rows applies.
+    pub num_rows: usize,
+    /// Number of bytes the driver has written into the transit buffer, in order to transfer the
+    /// values of this batch. Text and binary values are accounted for with their actual length, as
+    /// long as it fits into the buffer, values of fixed size types with their size. Indicators are
+    /// not included, so this is less than the size of the transit buffer needed for the rows.
+    pub transit_bytes: usize,
+    /// Time spent waiting for the ODBC driver to fetch the batch. For [`crate::OdbcReader`] this is
+    /// the time spent in `SQLFetch`. For [`crate::ConcurrentOdbcReader`] this is the time the
+    /// application thread had been blocked waiting for the fetch thread.
+    pub fetch_duration: Duration,
+    /// Time spent converting the contents of the transit buffer into an Arrow array, for each
+    /// column.
+    pub conversion_durations: Vec<Duration>,
+}
+
+/// Cumulative statistics over all batches read so far by a reader. See
+/// [`crate::OdbcReader::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReaderStats {
+    /// Number of rows fetched from the data source.
+    pub num_rows: usize,
+    /// Number of batches fetched from the data source.
+    pub num_batches: usize,
+    /// Sum of [`BatchStats::transit_bytes`] over all batches.
+    pub transit_bytes: usize,
+    /// Sum of [`BatchStats::fetch_duration`] over all batches.
+    pub fetch_duration: Duration,
+    /// Sum of [`BatchStats::conversion_durations`] over all batches, for each column.
+    pub conversion_durations: Vec<Duration>,
+}
+
+impl ReaderStats {
+    /// Total time spent converting the transit buffers into Arrow arrays, over all columns.
+    pub fn conversion_duration(&self) -> Duration {
+        self.conversion_durations.iter().sum()
+    }
+
+    /// Rows per second, taking into account the time spent fetching and converting. Time spent by
+    /// the application processing the record batches is not included. `None` if no time has been
+    /// spent yet.
+    pub fn rows_per_second(&self) -> Option<f64> {
+        let seconds = (self.fetch_duration + self.conversion_duration()).as_secs_f64();
+        if seconds == 0. {
+            None
+        } else {
+            Some(self.num_rows as f64 / seconds)
+        }
+    }
+}
+
+/// Callback invoked with the statistics of each batch.
+pub type ProgressCallback = Arc<dyn Fn(&BatchStats) + Send + Sync>;
+
+/// Keeps track of the statistics for a reader and reports them to the progress callback.
+pub struct StatsTracker {
+    cumulative: ReaderStats,
+    progress: Option<ProgressCallback>,
+}
+
+impl StatsTracker {
+    pub fn new(num_columns: usize, progress: Option<ProgressCallback>) -> Self {
+        Self {
+            cumulative: ReaderStats {
+                conversion_durations: vec![Duration::ZERO; num_columns],
+                ..ReaderStats::default()
+            },
+            progress,
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        num_rows: usize,
+        transit_bytes: usize,
+        fetch_duration: Duration,
+        conversion_durations: Vec<Duration>,
+    ) {
+        let batch = BatchStats {
+            num_rows,
+            transit_bytes,
+            fetch_duration,
+            conversion_durations,
+        };
+        self.cumulative.num_rows += batch.num_rows;
+        self.cumulative.num_batches += 1;
+        self.cumulative.transit_bytes += batch.transit_bytes;
+        self.cumulative.fetch_duration += batch.fetch_duration;
+        for (total, duration) in self
+            .cumulative
+            .conversion_durations
+            .iter_mut()
+            .zip(&batch.conversion_durations)
+        {
+            *total += *duration;
+        }
+        if let Some(progress) = &self.progress {
+            progress(&batch)
+        }
+    }
+
+    pub fn cumulative(&self) -> &ReaderStats {
+        &self.cumulative
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayRef, StructArray},
+    buffer::NullBuffer,
+    datatypes::{DataType, Field, Fields},
+};
+use odbc_api::ResultSetMetadata;
+
+use super::ColumnFailure;
+
+/// Describes how an array of the record batch is assembled from the arrays read from the columns of
+/// the transit buffer.
+pub enum ColumnLayout {
+    /// Array read from a single column. Holds the index of its buffer.
+    Column(usize),
+    /// Struct array, whose children are read from separate columns.
+    Struct {
+        fields: Fields,
+        nullable: bool,
+        children: Vec<ColumnLayout>,
+    },
+}
+
+impl ColumnLayout {
+    /// Layout of `field`, with its leaves read from the buffers in `buffer_indices`, in depth first
+    /// order.
+    pub fn new(field: &Field, buffer_indices: &mut impl Iterator<Item = usize>) -> Self {
+        match field.data_type() {
+            DataType::Struct(fields) => ColumnLayout::Struct {
+                fields: fields.clone(),
+                nullable: field.is_nullable(),
+                children: fields
+                    .iter()
+                    .map(|child| ColumnLayout::new(child, buffer_indices))
+                    .collect(),
+            },
+            _ => ColumnLayout::Column(buffer_indices.next().unwrap()),
+        }
+    }
+
+    /// `arrays` holds one array for each buffer of the transit buffer.
+    pub fn assemble(&self, arrays: &[ArrayRef]) -> ArrayRef {
+        match self {
+            ColumnLayout::Column(buffer_index) => arrays[*buffer_index].clone(),
+            ColumnLayout::Struct {
+                fields,
+                nullable,
+                children,
+            } => {
+                let children: Vec<_> = children
+                    .iter()
+                    .map(|child| child.assemble(arrays))
+                    .collect();
+                let nulls = if *nullable {
+                    all_children_null(&children)
+                } else {
+                    None
+                };
+                Arc::new(StructArray::new(fields.clone(), children, nulls))
+            }
+        }
+    }
+}
+
+/// A flat table can not tell a `NULL` struct from one whose children are all `NULL`. We assume the
+/// former, so round trips through [`crate::WriterOptions::flatten_structs`] preserve `NULL`s.
+fn all_children_null(children: &[ArrayRef]) -> Option<NullBuffer> {
+    let mut valid = None;
+    for child in children {
+        // A child without nulls, means every row of the parent is valid.
+        let child_valid = child.logical_nulls()?.into_inner();
+        valid = Some(match valid {
+            None => child_valid,
+            Some(valid) => &valid | &child_valid,
+        });
+    }
+    valid.map(NullBuffer::new)
+}
+
+/// Controls whether a `Struct` field is parsed from a single column holding JSON text, or
+/// assembled from one column for each of its leaves. See
+/// [`crate::OdbcReaderBuilder::with_struct_format`]. Parsing JSON text requires the `json`
+/// feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructFormat {
+    /// Parse the struct from JSON text in the column at the position of the field.
+    JsonText,
+    /// Assemble the struct from one column for each of its leaves, starting at the position of the
+    /// field.
+    Columns,
+}
+
+/// `true` if `field` is a struct, which is assembled from columns rather than parsed from JSON
+/// text. Structs which could be read either way, must have their format chosen explicitly.
+pub fn is_assembled_from_columns(
+    field: &Field,
+    format: Option<StructFormat>,
+) -> Result<bool, ColumnFailure> {
+    if !matches!(field.data_type(), DataType::Struct(_)) {
+        return Ok(false);
+    }
+    match format {
+        Some(StructFormat::Columns) => Ok(true),
+        Some(StructFormat::JsonText) if parsable_from_json(field.data_type()) => Ok(false),
+        Some(StructFormat::JsonText) => Err(ColumnFailure::UnsupportedArrowType(
+            field.data_type().clone(),
+        )),
+        None if parsable_from_json(field.data_type()) => Err(ColumnFailure::AmbiguousStructFormat),
+        None => Ok(true),
+    }
+}
+
+/// Without the `json` feature no field is parsed from JSON text, so structs are always assembled
+/// from columns.
+fn parsable_from_json(data_type: &DataType) -> bool {
+    #[cfg(feature = "json")]
+    {
+        super::JsonText::supports(data_type)
+    }
+    #[cfg(not(feature = "json"))]
+    {
+        let _ = data_type;
+        false
+    }
+}
+
+/// Pushes one field for each leaf of the struct `field` to `leaves`, in depth first order. Leaves
+/// are named after their path, e.g. `address.street`. Since the children of a `NULL` struct are
+/// `NULL` too, the children of nullable structs are nullable. Returns `field` with that
+/// nullability applied to its descendants.
+pub fn struct_leaves(field: &Field, leaves: &mut Vec<Field>) -> Result<Field, ColumnFailure> {
+    collect_leaves(field, field.name(), field.is_nullable(), leaves)
+}
+
+fn collect_leaves(
+    field: &Field,
+    path: &str,
+    nullable: bool,
+    leaves: &mut Vec<Field>,
+) -> Result<Field, ColumnFailure> {
+    let field = field.clone().with_nullable(nullable);
+    let DataType::Struct(children) = field.data_type() else {
+        leaves.push(field.clone().with_name(path));
+        return Ok(field);
+    };
+    // A struct without children has no column to tell its length.
+    if children.is_empty() {
+        return Err(ColumnFailure::UnsupportedArrowType(
+            field.data_type().clone(),
+        ));
+    }
+    let children = children
+        .iter()
+        .map(|child| {
+            let path = format!("{path}.{}", child.name());
+            let child = collect_leaves(child, &path, nullable || child.is_nullable(), leaves)?;
+            Ok(Arc::new(child))
+        })
+        .collect::<Result<Fields, _>>()?;
+    Ok(field.with_data_type(DataType::Struct(children)))
+}
+
+/// One based column number for each leaf. The leaves occupy the consecutive columns starting at
+/// `first_column`. Should every leaf find a column named after its path within these, the leaves
+/// are matched by name, otherwise by position.
+pub fn assign_columns(
+    leaves: &[Field],
+    cursor: &mut impl ResultSetMetadata,
+    first_column: u16,
+) -> Result<Vec<u16>, ColumnFailure> {
+    let column_numbers = (0..leaves.len()).map(|offset| first_column + offset as u16);
+    let column_names = column_numbers
+        .clone()
+        .map(|column_number| cursor.col_name(column_number))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ColumnFailure::FailedToDescribeColumn)?;
+    let mut by_name = Vec::new();
+    for leaf in leaves {
+        let Some(offset) = column_names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(leaf.name()))
+        else {
+            return Ok(column_numbers.collect());
+        };
+        if by_name.contains(&offset) {
+            return Ok(column_numbers.collect());
+        }
+        by_name.push(offset);
+    }
+    Ok(by_name
+        .into_iter()
+        .map(|offset| first_column + offset as u16)
+        .collect())
+}
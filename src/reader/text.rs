@@ -1,13 +1,16 @@
 use std::{char::decode_utf16, cmp::min, ffi::CStr, num::NonZeroUsize, sync::Arc};
 
-use arrow::array::{ArrayRef, StringBuilder};
+use arrow::{
+    array::{ArrayRef, BooleanBufferBuilder, StringArray, StringBuilder},
+    buffer::{Buffer, NullBuffer, OffsetBuffer, ScalarBuffer},
+};
 use log::warn;
 use odbc_api::{
     buffers::{AnySlice, BufferDesc},
     DataType as OdbcDataType,
 };
 
-use super::{ColumnFailure, MappingError, ReadStrategy};
+use super::{ColumnFailure, ReadStrategy, RowMappingError};
 
 /// This function decides wether this column will be queried as narrow (assumed to be utf-8) or
 /// wide text (assumed to be utf-16). The reason we do not always use narrow is that the encoding
@@ -18,14 +21,14 @@ pub fn choose_text_strategy(
     lazy_display_size: impl FnOnce() -> Result<Option<NonZeroUsize>, odbc_api::Error>,
     max_text_size: Option<usize>,
     assume_indicators_are_memory_garbage: bool,
-) -> Result<Box<dyn ReadStrategy + Send>, ColumnFailure> {
+) -> Result<Box<dyn ReadStrategy + Send + Sync>, ColumnFailure> {
     let apply_buffer_limit = |len| match (len, max_text_size) {
         (None, None) => Err(ColumnFailure::ZeroSizedColumn { sql_type }),
         (None, Some(limit)) => Ok(limit),
         (Some(len), None) => Ok(len),
         (Some(len), Some(limit)) => Ok(min(len, limit)),
     };
-    let strategy: Box<dyn ReadStrategy + Send> = if cfg!(target_os = "windows") {
+    let strategy: Box<dyn ReadStrategy + Send + Sync> = if cfg!(target_os = "windows") {
         let hex_len = sql_type
             .utf16_len()
             .map(Ok)
@@ -51,14 +54,14 @@ pub fn choose_text_strategy(
     Ok(strategy)
 }
 
-fn wide_text_strategy(u16_len: usize) -> Box<dyn ReadStrategy + Send> {
+fn wide_text_strategy(u16_len: usize) -> Box<dyn ReadStrategy + Send + Sync> {
     Box::new(WideText::new(u16_len))
 }
 
 fn narrow_text_strategy(
     octet_len: usize,
     assume_indicators_are_memory_garbage: bool,
-) -> Box<dyn ReadStrategy + Send> {
+) -> Box<dyn ReadStrategy + Send + Sync> {
     if assume_indicators_are_memory_garbage {
         warn!(
             "Ignoring indicators, because we expect the ODBC driver of your database to return \
@@ -92,7 +95,7 @@ impl ReadStrategy for WideText {
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
         let view = column_view.as_w_text_view().unwrap();
         let item_capacity = view.len();
         // Any utf-16 character could take up to 4 Bytes if represented as utf-8, but since mostly
@@ -136,16 +139,49 @@ impl ReadStrategy for NarrowText {
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
         let view = column_view.as_text_view().unwrap();
-        let mut builder = StringBuilder::with_capacity(view.len(), self.max_str_len * view.len());
-        for value in view.iter() {
-            builder.append_option(value.map(|bytes| {
-                std::str::from_utf8(bytes)
-                    .expect("ODBC driver had been expected to return valid utf8, but did not.")
-            }));
+        let num_rows = view.len();
+        let raw_values = view.raw_value_buffer();
+        // Each value in the ODBC buffer occupies the maximum string length plus terminating zero.
+        let stride = self.max_str_len + 1;
+        // Build offsets, validity and contiguous values in a single pass over the indicators.
+        let mut offsets = Vec::with_capacity(num_rows + 1);
+        offsets.push(0i32);
+        let mut validity = BooleanBufferBuilder::new(num_rows);
+        let mut values = Vec::with_capacity(self.max_str_len * num_rows);
+        for row_index in 0..num_rows {
+            if let Some(len) = view.content_length_at(row_index) {
+                let start = row_index * stride;
+                values.extend_from_slice(&raw_values[start..start + len]);
+                validity.append(true);
+            } else {
+                validity.append(false);
+            }
+            offsets.push(
+                values
+                    .len()
+                    .try_into()
+                    .expect("Text of a batch must not exceed 2GiB"),
+            );
         }
-        Ok(Arc::new(builder.finish()))
+        // Validate UTF-8 once for the entire batch. Since a valid buffer could still be split in
+        // the middle of a character, we also check every offset to be on a character boundary.
+        let text = std::str::from_utf8(&values)
+            .expect("ODBC driver had been expected to return valid utf8, but did not.");
+        assert!(
+            offsets
+                .iter()
+                .all(|&offset| text.is_char_boundary(offset as usize)),
+            "ODBC driver had been expected to return valid utf8, but did not."
+        );
+        let nulls = NullBuffer::new(validity.finish());
+        let nulls = (nulls.null_count() != 0).then_some(nulls);
+        let offsets = OffsetBuffer::new(ScalarBuffer::from(offsets));
+        // Safety: Offsets are monotonically increasing and point into values. We validated values
+        // to be UTF-8 and all offsets to be on character boundaries.
+        let array = unsafe { StringArray::new_unchecked(offsets, Buffer::from_vec(values), nulls) };
+        Ok(Arc::new(array))
     }
 }
 
@@ -167,7 +203,7 @@ impl ReadStrategy for NarrowUseTerminatingZero {
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, RowMappingError> {
         let view = column_view.as_text_view().unwrap();
         let mut builder = StringBuilder::with_capacity(view.len(), self.max_str_len * view.len());
         // We can not use view.iter() since its implementation relies on the indicator buffer being
@@ -1,62 +1,149 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use arrow::{
-    datatypes::{Schema, SchemaRef},
+    array::BooleanArray,
+    compute::filter_record_batch,
+    datatypes::{DataType, FieldRef, Schema, SchemaRef, TimeUnit},
     record_batch::RecordBatch,
 };
 use log::info;
 use odbc_api::{buffers::ColumnarAnyBuffer, ResultSetMetadata};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use crate::{arrow_schema_from, BufferAllocationOptions, ColumnFailure, Error};
+use crate::{
+    arrow_schema_with_column_names, BufferAllocationOptions, ColumnFailure, ColumnNameOptions,
+    Error, OutOfRangeTimestamp,
+};
 
-use super::{choose_column_strategy, MappingError, Quirks, ReadStrategy};
+use super::{
+    choose_column_strategy,
+    struct_columns::{
+        assign_columns, is_assembled_from_columns, struct_leaves, ColumnLayout, StructFormat,
+    },
+    transit_buffer::{IntervalColumn, NumericColumn, TransitBuffer},
+    ConversionError, DateTimeOptions, OnConversionError, Quirks, ReadStrategy, Reject,
+};
 
-/// Transforms batches fetched from an ODBC data source in a
-/// [`odbc_api::bufferers::ColumnarAnyBuffer`] into arrow tables of the specified schemas. It also
-/// allocates the buffers to hold the ODBC batches with the matching buffer descriptions.
+/// Transforms batches fetched from an ODBC data source in a [`TransitBuffer`] into arrow tables of
+/// the specified schemas. It also allocates the buffers to hold the ODBC batches with the matching
+/// buffer descriptions.
 pub struct ToRecordBatch {
-    /// Must contain one item for each field in [`Self::schema`]. Encapsulates all the column type
+    /// Contains one item for each column bound to the cursor. Encapsulates all the column type
     /// specific decisions which go into filling an Arrow array from an ODBC data source.
-    column_strategies: Vec<Box<dyn ReadStrategy + Send>>,
+    column_strategies: Vec<Box<dyn ReadStrategy + Send + Sync>>,
+    /// Field each column bound to the cursor is read as. Same as the fields of [`Self::schema`],
+    /// unless a struct is assembled from several columns. In this case it holds one field for each
+    /// of its leaves, named after their path, e.g. `address.street`.
+    columns: Vec<FieldRef>,
+    /// One item for each field in [`Self::schema`]. Describes how its array is assembled from the
+    /// arrays of the columns.
+    layouts: Vec<ColumnLayout>,
     /// Arrow schema describing the arrays we want to fill from the Odbc data source.
     schema: SchemaRef,
+    /// Wether values which can not be converted fail the entire batch or are recorded as rejects.
+    on_conversion_error: OnConversionError,
 }
 
 impl ToRecordBatch {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         cursor: &mut impl ResultSetMetadata,
         schema: Option<SchemaRef>,
+        column_name_options: ColumnNameOptions,
         buffer_allocation_options: BufferAllocationOptions,
         quirks: &Quirks,
+        on_conversion_error: OnConversionError,
+        date_time_options: DateTimeOptions,
+        struct_formats: &HashMap<String, StructFormat>,
     ) -> Result<Self, Error> {
         // Infer schema if not given by the user
         let schema = if let Some(schema) = schema {
             schema
         } else {
-            Arc::new(arrow_schema_from(cursor)?)
+            let schema = arrow_schema_with_column_names(cursor, column_name_options)?;
+            if date_time_options.out_of_range_timestamp == OutOfRangeTimestamp::Microseconds {
+                Arc::new(nanoseconds_to_microseconds(schema))
+            } else {
+                Arc::new(schema)
+            }
         };
 
-        let column_strategies: Vec<Box<dyn ReadStrategy + Send>> = schema
-            .fields()
-            .iter()
-            .enumerate()
-            .map(|(index, field)| {
-                let col_index = (index + 1).try_into().unwrap();
-                choose_column_strategy(field, cursor, col_index, buffer_allocation_options, quirks)
-                    .map_err(|cause| cause.into_crate_error(field.name().clone(), index))
-            })
-            .collect::<Result<_, _>>()?;
+        let mut column_strategies: Vec<Box<dyn ReadStrategy + Send + Sync>> = Vec::new();
+        let mut columns = Vec::new();
+        let mut layouts = Vec::new();
+        let mut fields = Vec::new();
+        for field in schema.fields() {
+            let index = columns.len();
+            let col_index = (index + 1).try_into().unwrap();
+            let format = struct_formats.get(field.name()).copied();
+            let assembled = is_assembled_from_columns(field, format)
+                .map_err(|cause| cause.into_crate_error(field.name().clone(), index))?;
+            if !assembled {
+                let strategy = choose_column_strategy(
+                    field,
+                    cursor,
+                    col_index,
+                    buffer_allocation_options,
+                    quirks,
+                    date_time_options,
+                )
+                .map_err(|cause| cause.into_crate_error(field.name().clone(), index))?;
+                column_strategies.push(strategy);
+                columns.push(field.clone());
+                layouts.push(ColumnLayout::Column(index));
+                fields.push(field.clone());
+                continue;
+            }
+            // Read each leaf of the struct from its own column
+            let mut leaves = Vec::new();
+            let field = struct_leaves(field, &mut leaves)
+                .map_err(|cause| cause.into_crate_error(field.name().clone(), index))?;
+            let column_numbers = assign_columns(&leaves, cursor, col_index)
+                .map_err(|cause| cause.into_crate_error(field.name().clone(), index))?;
+            // Columns are bound in order, so we choose the strategies in the order of the columns,
+            // rather than the one of the leaves.
+            let mut by_column: Vec<_> = column_numbers.iter().zip(leaves).collect();
+            by_column.sort_by_key(|(column_number, _)| **column_number);
+            for (&column_number, leaf) in by_column {
+                let leaf_index = usize::from(column_number - 1);
+                let strategy = choose_column_strategy(
+                    &leaf,
+                    cursor,
+                    column_number,
+                    buffer_allocation_options,
+                    quirks,
+                    date_time_options,
+                )
+                .map_err(|cause| cause.into_crate_error(leaf.name().clone(), leaf_index))?;
+                column_strategies.push(strategy);
+                columns.push(Arc::new(leaf));
+            }
+            let mut buffer_indices = column_numbers
+                .iter()
+                .map(|column_number| usize::from(column_number - 1));
+            layouts.push(ColumnLayout::new(&field, &mut buffer_indices));
+            fields.push(Arc::new(field));
+        }
+        let schema = Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()));
 
         Ok(ToRecordBatch {
             column_strategies,
+            columns,
+            layouts,
             schema,
+            on_conversion_error,
         })
     }
 
     /// Logs buffer description and sizes
     pub fn row_size_in_bytes(&self) -> usize {
         let mut total_bytes = 0;
-        for (read, field) in self.column_strategies.iter().zip(self.schema.fields()) {
+        for (read, field) in self.column_strategies.iter().zip(&self.columns) {
             let name = field.name();
             let desc = read.buffer_desc();
             let bytes_per_row = desc.bytes_per_row();
@@ -71,48 +158,178 @@ impl ToRecordBatch {
         &self,
         max_batch_size: usize,
         fallibale_allocations: bool,
-    ) -> Result<ColumnarAnyBuffer, Error> {
+    ) -> Result<TransitBuffer, Error> {
         let descs = self.column_strategies.iter().map(|cs| cs.buffer_desc());
 
         let row_set_buffer = if fallibale_allocations {
             ColumnarAnyBuffer::try_from_descs(max_batch_size, descs)
-                .map_err(|err| map_allocation_error(err, &self.schema))?
+                .map_err(|err| map_allocation_error(err, &self.columns))?
         } else {
             ColumnarAnyBuffer::from_descs(max_batch_size, descs)
         };
-        Ok(row_set_buffer)
+        let numeric_columns = self
+            .column_strategies
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cs)| {
+                cs.numeric_precision_and_scale()
+                    .map(|(precision, scale)| NumericColumn {
+                        column_number: (index + 1).try_into().unwrap(),
+                        precision,
+                        scale,
+                    })
+            })
+            .collect();
+        let interval_columns = self
+            .column_strategies
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cs)| {
+                cs.interval_kind().map(|kind| IntervalColumn {
+                    column_number: (index + 1).try_into().unwrap(),
+                    kind,
+                })
+            })
+            .collect();
+        Ok(TransitBuffer::new(
+            row_set_buffer,
+            numeric_columns,
+            interval_columns,
+        ))
     }
 
     pub fn schema(&self) -> &SchemaRef {
         &self.schema
     }
 
+    /// Number of columns bound to the cursor. Larger than the number of fields in the schema, if
+    /// structs are assembled from several columns.
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Converts the contents of `odbc_buffer` into a record batch. The time spent converting each
+    /// column is pushed to `conversion_durations`. `first_row_index` is the index of the first row
+    /// in the buffer, counting from the start of the cursor. It is used to report the position of
+    /// values which can not be converted. Depending on [`OnConversionError`] such values are pushed
+    /// to `rejects`, rather than failing the entire batch.
     pub fn buffer_to_record_batch(
         &self,
-        odbc_buffer: &ColumnarAnyBuffer,
-    ) -> Result<RecordBatch, MappingError> {
-        let arrow_columns = self
+        odbc_buffer: &TransitBuffer,
+        first_row_index: usize,
+        conversion_durations: &mut Vec<Duration>,
+        rejects: &mut Vec<Reject>,
+    ) -> Result<RecordBatch, ConversionError> {
+        let convert_column = |(index, strat): (usize, &(dyn ReadStrategy + Send + Sync))| {
+            let start = Instant::now();
+            let column_view = odbc_buffer.column(index);
+            let mut column_rejects = Vec::new();
+            let array = if self.on_conversion_error == OnConversionError::Fail {
+                strat.fill_arrow_array(column_view)
+            } else {
+                strat.fill_arrow_array_recording_rejects(column_view, &mut column_rejects)
+            }
+            .map_err(|error| ConversionError {
+                column_index: index,
+                column_name: self.columns[index].name().clone(),
+                row_index: error.row_index,
+                absolute_row_index: first_row_index + error.row_index,
+                source: error.source,
+            });
+            (array, column_rejects, start.elapsed())
+        };
+        // With the `rayon` feature, columns are converted in parallel. We still collect all the
+        // results before looking at them, so the error reported is always the one of the first
+        // failing column.
+        #[cfg(feature = "rayon")]
+        let converted: Vec<_> = self
+            .column_strategies
+            .par_iter()
+            .enumerate()
+            .map(|(index, strat)| convert_column((index, strat.as_ref())))
+            .collect();
+        #[cfg(not(feature = "rayon"))]
+        let converted: Vec<_> = self
             .column_strategies
             .iter()
             .enumerate()
-            .map(|(index, strat)| {
-                let column_view = odbc_buffer.column(index);
-                strat.fill_arrow_array(column_view)
+            .map(|(index, strat)| convert_column((index, strat.as_ref())))
+            .collect();
+        // Rows containing rejected values, which can not be represented in the record batch.
+        let mut dropped_rows = Vec::new();
+        // Only appended to `rejects` once every column has been converted. Should a later column
+        // fail, the batch is not emitted and its rejects must not be reported either.
+        let mut batch_rejects = Vec::new();
+        let arrays = converted
+            .into_iter()
+            .enumerate()
+            .map(|(column_index, (array, column_rejects, duration))| {
+                conversion_durations.push(duration);
+                let field = &self.columns[column_index];
+                for reject in column_rejects {
+                    if self.on_conversion_error == OnConversionError::DropAndRecord
+                        || !field.is_nullable()
+                    {
+                        dropped_rows.push(reject.row_index);
+                    }
+                    batch_rejects.push(Reject {
+                        row_number: first_row_index + reject.row_index,
+                        column_index,
+                        column_name: field.name().clone(),
+                        raw_value: reject.raw_value,
+                        error: reject.source,
+                    });
+                }
+                array
             })
             .collect::<Result<Vec<_>, _>>()?;
+        rejects.append(&mut batch_rejects);
+        let arrow_columns = self
+            .layouts
+            .iter()
+            .map(|layout| layout.assemble(&arrays))
+            .collect();
         let record_batch = RecordBatch::try_new(self.schema.clone(), arrow_columns).unwrap();
+        if dropped_rows.is_empty() {
+            return Ok(record_batch);
+        }
+        let mut keep = vec![true; record_batch.num_rows()];
+        for row_index in dropped_rows {
+            keep[row_index] = false;
+        }
+        let record_batch = filter_record_batch(&record_batch, &BooleanArray::from(keep)).unwrap();
         Ok(record_batch)
     }
 }
 
-fn map_allocation_error(error: odbc_api::Error, schema: &Schema) -> Error {
+/// Replaces nanoseconds precision timestamps with microseconds precision ones. Since nanoseconds are
+/// only inferred for columns with more than six fractional digits, the remaining digits are
+/// truncated. Metadata of the schema and its fields is kept.
+fn nanoseconds_to_microseconds(schema: Schema) -> Schema {
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::Timestamp(TimeUnit::Nanosecond, time_zone) => {
+                field.as_ref().clone().with_data_type(DataType::Timestamp(
+                    TimeUnit::Microsecond,
+                    time_zone.clone(),
+                ))
+            }
+            _ => field.as_ref().clone(),
+        })
+        .collect();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+fn map_allocation_error(error: odbc_api::Error, columns: &[FieldRef]) -> Error {
     match error {
         odbc_api::Error::TooLargeColumnBufferSize {
             buffer_index,
             num_elements,
             element_size,
         } => Error::ColumnFailure {
-            name: schema.field(buffer_index as usize).name().clone(),
+            name: columns[buffer_index as usize].name().clone(),
             index: buffer_index as usize,
             source: ColumnFailure::TooLarge {
                 num_elements,
@@ -124,3 +341,31 @@ fn map_allocation_error(error: odbc_api::Error, schema: &Schema) -> Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+
+    use super::nanoseconds_to_microseconds;
+
+    #[test]
+    fn nanoseconds_to_microseconds_keeps_metadata() {
+        let metadata = HashMap::from([("key".to_owned(), "value".to_owned())]);
+        let field = Field::new("a", DataType::Timestamp(TimeUnit::Nanosecond, None), false)
+            .with_metadata(metadata.clone());
+        let schema = Schema::new_with_metadata(vec![field], metadata.clone());
+
+        let schema = nanoseconds_to_microseconds(schema);
+
+        let field = schema.field(0);
+        assert_eq!(
+            &DataType::Timestamp(TimeUnit::Microsecond, None),
+            field.data_type()
+        );
+        assert!(!field.is_nullable());
+        assert_eq!(&metadata, field.metadata());
+        assert_eq!(&metadata, schema.metadata());
+    }
+}
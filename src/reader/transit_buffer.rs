@@ -0,0 +1,153 @@
+use std::mem::size_of_val;
+
+use odbc_api::{
+    buffers::{AnySlice, BufferDesc, ColumnarAnyBuffer, Item, NullableSlice},
+    handles::{AsStatementRef, Statement, StatementRef},
+    Error, RowSetBuffer, TruncationInfo,
+};
+
+use crate::{
+    interval::{bind_column_as_interval, IntervalKind, INTERVAL_STRUCT_SIZE},
+    numeric::{bind_column_as_numeric, NUMERIC_STRUCT_SIZE},
+};
+
+/// Describes a column which is fetched as `SQL_NUMERIC_STRUCT`.
+#[derive(Clone, Copy)]
+pub struct NumericColumn {
+    /// One based column number
+    pub column_number: u16,
+    pub precision: u8,
+    pub scale: i8,
+}
+
+/// Describes a column which is fetched as `SQL_INTERVAL_STRUCT`.
+#[derive(Clone, Copy)]
+pub struct IntervalColumn {
+    /// One based column number
+    pub column_number: u16,
+    pub kind: IntervalKind,
+}
+
+/// Buffer bound to the cursor in order to transit values from the data source into Arrow arrays.
+/// Behaves like the [`ColumnarAnyBuffer`] it wraps, but allows to fetch some columns as
+/// `SQL_NUMERIC_STRUCT` or `SQL_INTERVAL_STRUCT`. `odbc-api` does not offer buffers for these
+/// structs. So we allocate binary columns of the same size and alter the application row
+/// descriptor after binding them.
+pub struct TransitBuffer {
+    buffer: ColumnarAnyBuffer,
+    numeric_columns: Vec<NumericColumn>,
+    interval_columns: Vec<IntervalColumn>,
+}
+
+impl TransitBuffer {
+    pub fn new(
+        buffer: ColumnarAnyBuffer,
+        numeric_columns: Vec<NumericColumn>,
+        interval_columns: Vec<IntervalColumn>,
+    ) -> Self {
+        Self {
+            buffer,
+            numeric_columns,
+            interval_columns,
+        }
+    }
+
+    /// Buffer description of a column which is going to be fetched as `SQL_NUMERIC_STRUCT`.
+    pub fn numeric_buffer_desc() -> BufferDesc {
+        BufferDesc::Binary {
+            length: NUMERIC_STRUCT_SIZE,
+        }
+    }
+
+    /// Buffer description of a column which is going to be fetched as `SQL_INTERVAL_STRUCT`.
+    pub fn interval_buffer_desc() -> BufferDesc {
+        BufferDesc::Binary {
+            length: INTERVAL_STRUCT_SIZE,
+        }
+    }
+
+    pub fn column(&self, buffer_index: usize) -> AnySlice<'_> {
+        self.buffer.column(buffer_index)
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.buffer.num_rows()
+    }
+
+    /// Number of bytes the driver has written into the value buffers for the rows of the current
+    /// batch. Variadic columns account for the length of each value, as reported by its indicator,
+    /// limited to the size of the buffer element. Indicators are not accounted for.
+    pub fn transferred_bytes(&self) -> usize {
+        (0..self.buffer.num_cols())
+            .map(|buffer_index| value_bytes(self.column(buffer_index)))
+            .sum()
+    }
+}
+
+fn value_bytes(column: AnySlice) -> usize {
+    match column {
+        AnySlice::Text(view) => view.iter().flatten().map(size_of_val).sum(),
+        AnySlice::WText(view) => view.iter().flatten().map(size_of_val).sum(),
+        AnySlice::Binary(view) => view.iter().flatten().map(size_of_val).sum(),
+        AnySlice::Date(values) => size_of_val(values),
+        AnySlice::Time(values) => size_of_val(values),
+        AnySlice::Timestamp(values) => size_of_val(values),
+        AnySlice::F64(values) => size_of_val(values),
+        AnySlice::F32(values) => size_of_val(values),
+        AnySlice::I8(values) => size_of_val(values),
+        AnySlice::I16(values) => size_of_val(values),
+        AnySlice::I32(values) => size_of_val(values),
+        AnySlice::I64(values) => size_of_val(values),
+        AnySlice::U8(values) => size_of_val(values),
+        AnySlice::Bit(values) => size_of_val(values),
+        AnySlice::NullableDate(values) => nullable_value_bytes(values),
+        AnySlice::NullableTime(values) => nullable_value_bytes(values),
+        AnySlice::NullableTimestamp(values) => nullable_value_bytes(values),
+        AnySlice::NullableF64(values) => nullable_value_bytes(values),
+        AnySlice::NullableF32(values) => nullable_value_bytes(values),
+        AnySlice::NullableI8(values) => nullable_value_bytes(values),
+        AnySlice::NullableI16(values) => nullable_value_bytes(values),
+        AnySlice::NullableI32(values) => nullable_value_bytes(values),
+        AnySlice::NullableI64(values) => nullable_value_bytes(values),
+        AnySlice::NullableU8(values) => nullable_value_bytes(values),
+        AnySlice::NullableBit(values) => nullable_value_bytes(values),
+    }
+}
+
+fn nullable_value_bytes<T: Item>(values: NullableSlice<T>) -> usize {
+    size_of_val(values.raw_values().0)
+}
+
+unsafe impl RowSetBuffer for TransitBuffer {
+    fn bind_type(&self) -> usize {
+        self.buffer.bind_type()
+    }
+
+    fn row_array_size(&self) -> usize {
+        self.buffer.row_array_size()
+    }
+
+    fn mut_num_fetch_rows(&mut self) -> &mut usize {
+        self.buffer.mut_num_fetch_rows()
+    }
+
+    unsafe fn bind_colmuns_to_cursor(&mut self, mut cursor: StatementRef<'_>) -> Result<(), Error> {
+        self.buffer.bind_colmuns_to_cursor(cursor.as_stmt_ref())?;
+        for column in &self.numeric_columns {
+            bind_column_as_numeric(
+                cursor.as_sys(),
+                column.column_number,
+                column.precision,
+                column.scale,
+            )?;
+        }
+        for column in &self.interval_columns {
+            bind_column_as_interval(cursor.as_sys(), column.column_number, column.kind)?;
+        }
+        Ok(())
+    }
+
+    fn find_truncation(&self) -> Option<TruncationInfo> {
+        self.buffer.find_truncation()
+    }
+}
@@ -1,9 +1,9 @@
-use arrow::datatypes::{DataType as ArrowDataType, Field, Schema, TimeUnit};
+use arrow::datatypes::{DataType as ArrowDataType, Field, IntervalUnit, Schema, TimeUnit};
 use log::debug;
 use odbc_api::{ColumnDescription, DataType as OdbcDataType, ResultSetMetadata};
-use std::convert::TryInto;
+use std::{collections::HashSet, convert::TryInto};
 
-use crate::{ColumnFailure, Error};
+use crate::{interval::IntervalKind, ColumnFailure, Error};
 
 /// Query the metadata to create an arrow schema. This method is invoked automatically for you by
 /// [`crate::OdbcReader::new`]. You may want to call this method in situtation ther you want to
@@ -30,12 +30,48 @@ use crate::{ColumnFailure, Error};
 /// }
 /// ```
 pub fn arrow_schema_from(resut_set_metadata: &mut impl ResultSetMetadata) -> Result<Schema, Error> {
+    arrow_schema_with_column_names(resut_set_metadata, ColumnNameOptions::default())
+}
+
+/// Like [`arrow_schema_from`], but allows to control how the column names reported by the ODBC
+/// driver are turned into names of the Arrow fields. E.g. a query like `SELECT a.id, b.id FROM
+/// a, b` would otherwise yield two fields with the same name.
+///
+/// # Example
+///
+/// ```
+/// use anyhow::Error;
+///
+/// use arrow_odbc::{
+///     arrow_schema_with_column_names, arrow::datatypes::Schema, odbc_api::Connection,
+///     ColumnNameCase, ColumnNameOptions
+/// };
+///
+/// fn fetch_schema_with_unique_lowercase_names(
+///     sql: &str,
+///     connection: &Connection<'_>
+/// ) -> Result<Schema, Error> {
+///     let mut prepared = connection.prepare(sql)?;
+///     let options = ColumnNameOptions {
+///         unique: true,
+///         case: ColumnNameCase::Lower,
+///     };
+///     let schema = arrow_schema_with_column_names(&mut prepared, options)?;
+///     Ok(schema)
+/// }
+/// ```
+pub fn arrow_schema_with_column_names(
+    resut_set_metadata: &mut impl ResultSetMetadata,
+    column_name_options: ColumnNameOptions,
+) -> Result<Schema, Error> {
     let num_cols: u16 = resut_set_metadata
         .num_result_cols()
         .map_err(Error::UnableToRetrieveNumCols)?
         .try_into()
         .unwrap();
     let mut fields = Vec::new();
+    // Names already taken by previous fields. Only used if names are to be made unique.
+    let mut used_names = HashSet::new();
     for index in 0..num_cols {
         let mut column_description = ColumnDescription::default();
         resut_set_metadata
@@ -48,6 +84,7 @@ pub fn arrow_schema_from(resut_set_metadata: &mut impl ResultSetMetadata) -> Res
         let name = column_description
             .name_to_string()
             .map_err(|source| Error::EncodingInvalid { source })?;
+        let name = column_name_options.apply(name, index as usize, &mut used_names);
         debug!(
             "ODBC driver reported for column {index}. Relational type: {:?}; Nullability: {:?}; \
             Name: '{name}';",
@@ -102,15 +139,23 @@ pub fn arrow_schema_from(resut_set_metadata: &mut impl ResultSetMetadata) -> Res
             OdbcDataType::LongVarbinary { length: _ } | OdbcDataType::Varbinary { length: _ } => {
                 ArrowDataType::Binary
             }
+            OdbcDataType::Other { data_type, .. } => {
+                match IntervalKind::from_sql_data_type(data_type) {
+                    Some(IntervalKind::YearToMonth) => {
+                        ArrowDataType::Interval(IntervalUnit::YearMonth)
+                    }
+                    // Month day nano is the only Arrow interval type which can hold fractions
+                    // finer than milliseconds.
+                    Some(IntervalKind::DayToSecond) => {
+                        ArrowDataType::Interval(IntervalUnit::MonthDayNano)
+                    }
+                    None => ArrowDataType::Utf8,
+                }
+            }
             OdbcDataType::Unknown
             | OdbcDataType::Time { precision: _ }
             | OdbcDataType::Numeric { .. }
             | OdbcDataType::Decimal { .. }
-            | OdbcDataType::Other {
-                data_type: _,
-                column_size: _,
-                decimal_digits: _,
-            }
             | OdbcDataType::WChar { length: _ }
             | OdbcDataType::Char { length: _ }
             | OdbcDataType::WVarchar { length: _ }
@@ -123,3 +168,58 @@ pub fn arrow_schema_from(resut_set_metadata: &mut impl ResultSetMetadata) -> Res
     }
     Ok(Schema::new(fields))
 }
+
+/// Controls how the column names reported by the ODBC driver are turned into the names of the
+/// fields of an inferred Arrow schema. Used by [`arrow_schema_with_column_names`] and
+/// [`crate::OdbcReaderBuilder::with_column_name_options`]. The default leaves the names untouched.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnNameOptions {
+    /// Set to `true` in order to guarantee unique, non empty field names. Columns without a name
+    /// (e.g. `SELECT COUNT(*)`) are named after their zero based position, e.g. `column_3`. If a
+    /// name has already been taken by a previous column, a suffix `_1`, `_2`, ... is appended
+    /// until the name is unique. E.g. `SELECT a.id, b.id` yields `id` and `id_1`. Uniqueness is
+    /// established after case normalization has been applied.
+    pub unique: bool,
+    /// Case normalization applied to every column name.
+    pub case: ColumnNameCase,
+}
+
+/// Case normalization applied to column names, see [`ColumnNameOptions`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnNameCase {
+    /// Use the names exactly as reported by the ODBC driver.
+    #[default]
+    Preserve,
+    /// Convert names to lower case.
+    Lower,
+    /// Convert names to upper case.
+    Upper,
+}
+
+impl ColumnNameOptions {
+    /// Transforms the name reported by the driver for the column at the zero based `index`.
+    /// `used_names` holds the names of all previous columns and is updated with the result.
+    fn apply(&self, name: String, index: usize, used_names: &mut HashSet<String>) -> String {
+        let name = match self.case {
+            ColumnNameCase::Preserve => name,
+            ColumnNameCase::Lower => name.to_lowercase(),
+            ColumnNameCase::Upper => name.to_uppercase(),
+        };
+        if !self.unique {
+            return name;
+        }
+        let name = if name.is_empty() {
+            format!("column_{index}")
+        } else {
+            name
+        };
+        let mut candidate = name.clone();
+        let mut suffix = 1;
+        while used_names.contains(&candidate) {
+            candidate = format!("{name}_{suffix}");
+            suffix += 1;
+        }
+        used_names.insert(candidate.clone());
+        candidate
+    }
+}
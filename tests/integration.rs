@@ -1,17 +1,22 @@
-use std::{sync::Arc, thread};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
 
 use arrow::{
     array::{
-        Array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Date64Array, Decimal128Array,
-        Decimal256Builder, FixedSizeBinaryArray, Float16Array, Float32Array, Int16Array,
-        Int32Array, Int64Array, Int8Array, LargeStringArray, StringArray, Time32MillisecondArray,
-        Time32SecondArray, Time64MicrosecondArray, Time64NanosecondArray,
-        TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
-        TimestampSecondArray, UInt8Array,
+        Array, ArrayRef, AsArray, BinaryArray, BooleanArray, Date32Array, Date64Array,
+        Decimal128Array, Decimal256Builder, DurationMillisecondArray, FixedSizeBinaryArray,
+        Float16Array, Float32Array, Int16Array, Int32Array, Int64Array, Int8Array,
+        IntervalMonthDayNanoArray, IntervalYearMonthArray, LargeBinaryArray, LargeStringArray,
+        StringArray, StructArray, Time32MillisecondArray, Time32SecondArray,
+        Time64MicrosecondArray, Time64NanosecondArray, TimestampMicrosecondArray,
+        TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray, UInt16Array,
+        UInt32Array, UInt64Array, UInt8Array,
     },
     datatypes::{
-        ArrowPrimitiveType, DataType, Decimal256Type, Field, Float16Type, Schema, SchemaRef,
-        TimeUnit,
+        ArrowPrimitiveType, DataType, Decimal256Type, Field, Float16Type, Int32Type,
+        IntervalMonthDayNanoType, IntervalUnit, IntervalYearMonthType, Schema, SchemaRef, TimeUnit,
     },
     error::ArrowError,
     record_batch::{RecordBatch, RecordBatchReader},
@@ -26,14 +31,20 @@ type F16 = <Float16Type as ArrowPrimitiveType>::Native;
 
 use arrow_odbc::{
     arrow::array::Float64Array,
-    arrow_schema_from, insert_into_table,
+    arrow_schema_from, columns_as_arrow, columns_schema, create_table_statement_from_schema,
+    flatten_schema, foreign_keys_as_arrow, foreign_keys_schema, insert_into_table,
+    insert_statement_from_schema,
     odbc_api::{
         buffers::TextRowSet,
         sys::{AttrConnectionPooling, AttrCpMatch},
         Connection, ConnectionOptions, Cursor, CursorImpl, Environment, IntoParameter,
         StatementConnection,
     },
-    ColumnFailure, Error, OdbcReaderBuilder, OdbcWriter, Quirks, WriterError,
+    primary_keys_as_arrow, primary_keys_schema, statistics_as_arrow, statistics_schema,
+    tables_as_arrow, tables_schema, Cancelled, ColumnFailure, ColumnNameCase, ColumnNameOptions,
+    ConversionError, DurationFormat, Error, IntervalFormat, MappingError, OdbcReaderBuilder,
+    OdbcWriter, OnConversionError, OnInvalidDateTime, OutOfRangeTimestamp, PartitionSpec,
+    PartitionedReader, Quirks, SqlDialect, StructFormat, WriterError, WriterOptions,
 };
 
 use stdext::function_name;
@@ -44,6 +55,15 @@ const MSSQL: &str = "Driver={ODBC Driver 17 for SQL Server};\
     UID=SA;\
     PWD=My@Test@Password1;";
 
+/// Connection string to our PostgreSQL Database, for features Microsoft SQL Server lacks, like
+/// interval types. Boot it up with docker-compose up
+const POSTGRES: &str = "Driver={PostgreSQL UNICODE};\
+    Server=localhost;\
+    Port=5432;\
+    Database=test;\
+    Uid=test;\
+    Pwd=test;";
+
 // Rust by default executes tests in parallel. Yet only one environment is allowed at a time.
 lazy_static! {
     static ref ENV: Environment = unsafe {
@@ -72,6 +92,32 @@ fn fetch_nullable_32bit_integer() {
     assert_eq!([1, 0, 3], *array_vals.values());
 }
 
+/// Values at the position of `NULL`s must not leak from the previous batch fetched into the same
+/// transit buffer.
+#[test]
+fn nullable_integer_does_not_leak_previous_batch() {
+    // Given a cursor over a value followed by NULL
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(42),(NULL)");
+
+    // When reading it one row at a time
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(1)
+        .build(cursor)
+        .unwrap();
+    let _first = reader.next().unwrap().unwrap();
+    let second = reader.next().unwrap().unwrap();
+
+    // Then
+    let array_vals = second
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert!(array_vals.is_null(0));
+    assert_eq!([0], *array_vals.values());
+}
+
 /// Fill a record batch with non nullable Integer 32 Bit directly from the datasource
 #[test]
 fn fetch_32bit_integer() {
@@ -300,6 +346,23 @@ fn fetch_varchar() {
     assert!(array_vals.is_null(2));
 }
 
+/// Offsets and validity of text are computed for the entire batch at once. Empty strings and `NULL`
+/// must stay distinct.
+#[test]
+fn fetch_varchar_with_empty_strings_and_nulls() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+
+    let array_any =
+        fetch_arrow_data(table_name, "VARCHAR(50)", "(''),(NULL),('Hello'),('')").unwrap();
+
+    let array_vals = array_any.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!("", array_vals.value(0));
+    assert!(array_vals.is_null(1));
+    assert_eq!("Hello", array_vals.value(2));
+    assert_eq!("", array_vals.value(3));
+    assert_eq!(1, array_vals.null_count());
+}
+
 /// IBM DB2 ODBC driver reports memory garbage instead of indicators. We want to activate a
 /// workaround which utilizes the terminating zeroes instead of indicators to determine string
 /// length and NULL.
@@ -598,7 +661,9 @@ fn fetch_out_of_range_date_time_ns() {
     .unwrap_err();
 
     assert_eq!(
-        "External error: Timestamp is not representable in arrow: 2300-01-01 00:00:00.123456700\n\
+        "External error: Failed to convert the value in column 'a' (index 0) at row 0 of the \
+        batch (row 0 of the result set).\n\
+        Timestamp is not representable in arrow: 2300-01-01 00:00:00.123456700\n\
         Timestamps with nanoseconds precision are represented using a signed 64 Bit integer. This \
         limits their range to values between 1677-09-21 00:12:44 and \
         2262-04-11 23:47:16.854775807. The value returned from the database is outside of this \
@@ -655,6 +720,59 @@ fn fetch_negative_decimal() {
     assert_eq!("-123.45", array_vals.value_as_string(0));
 }
 
+/// Fetch decimals as `SQL_NUMERIC_STRUCT` rather than text. Read concurrently in small batches, so
+/// buffers are bound to the cursor more than once.
+#[test]
+fn fetch_decimals_as_numeric_struct() {
+    // Given a cursor over a table with one decimal column
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(
+        table_name,
+        "DECIMAL(5,2)",
+        "(123.45),(-678.90),(NULL),(0.01)",
+    );
+
+    // When fetching it as numeric structs
+    let mut quirks = Quirks::new();
+    quirks.fetch_decimals_as_numeric_struct = true;
+    let reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(2)
+        .with_shims(quirks)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+    // Then
+    let values: Vec<_> = batches
+        .iter()
+        .flat_map(|batch| {
+            let array_vals = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Decimal128Array>()
+                .unwrap();
+            (0..array_vals.len())
+                .map(|index| {
+                    array_vals
+                        .is_valid(index)
+                        .then(|| array_vals.value_as_string(index))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            Some("123.45".to_owned()),
+            Some("-678.90".to_owned()),
+            None,
+            Some("0.01".to_owned())
+        ],
+        values
+    );
+}
+
 /// Fetch variable sized binary data binary data
 #[test]
 fn fetch_varbinary_data() {
@@ -804,6 +922,33 @@ fn infer_schema() {
     assert_eq!(expected, actual)
 }
 
+#[test]
+fn infer_schema_with_unique_lower_case_column_names() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+
+    // Given a query with duplicate and empty column names
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["INTEGER NOT NULL"]).unwrap();
+    let sql = format!("SELECT a AS ID, a AS id, COUNT(*) FROM {table_name} GROUP BY a");
+    let cursor = conn.execute(&sql, ()).unwrap().unwrap();
+
+    // When
+    let reader = OdbcReaderBuilder::new()
+        .with_column_name_options(ColumnNameOptions {
+            unique: true,
+            case: ColumnNameCase::Lower,
+        })
+        .build(cursor)
+        .unwrap();
+
+    // Then
+    let schema = reader.schema();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(["id", "id_1", "column_2"], names.as_slice());
+}
+
 #[test]
 fn fetch_schema_for_table() {
     let table_name = function_name!().rsplit_once(':').unwrap().1;
@@ -834,6 +979,226 @@ fn fetch_schema_for_table() {
     )
 }
 
+#[test]
+fn fetch_tables_as_arrow() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+
+    // Given a table
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["INTEGER"]).unwrap();
+
+    // When
+    let mut reader =
+        tables_as_arrow(&conn, "", "", table_name, "", &OdbcReaderBuilder::new()).unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    assert_eq!(Arc::new(tables_schema()), batch.schema());
+    let table_names = batch
+        .column_by_name("TABLE_NAME")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(1, table_names.len());
+    assert_eq!(table_name, table_names.value(0));
+    let table_types = batch
+        .column_by_name("TABLE_TYPE")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!("TABLE", table_types.value(0));
+}
+
+#[test]
+fn fetch_columns_as_arrow() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+
+    // Given a table with an identity column `id` and a column `a`
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["VARCHAR(50) NOT NULL"]).unwrap();
+
+    // When
+    let mut reader =
+        columns_as_arrow(&conn, "", "", table_name, "", &OdbcReaderBuilder::new()).unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    assert_eq!(Arc::new(columns_schema()), batch.schema());
+    let column_names = batch
+        .column_by_name("COLUMN_NAME")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(
+        vec![Some("id"), Some("a")],
+        column_names.iter().collect::<Vec<_>>()
+    );
+    let column_sizes = batch
+        .column_by_name("COLUMN_SIZE")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(50, column_sizes.value(1));
+}
+
+#[test]
+fn fetch_foreign_keys_as_arrow() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let referenced_table = format!("{table_name}_referenced");
+
+    // Given a table referencing another one
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    conn.execute(&format!("DROP TABLE IF EXISTS {table_name}"), ())
+        .unwrap();
+    conn.execute(&format!("DROP TABLE IF EXISTS {referenced_table}"), ())
+        .unwrap();
+    conn.execute(
+        &format!("CREATE TABLE {referenced_table} (id INTEGER PRIMARY KEY)"),
+        (),
+    )
+    .unwrap();
+    conn.execute(
+        &format!("CREATE TABLE {table_name} (a INTEGER REFERENCES {referenced_table}(id))"),
+        (),
+    )
+    .unwrap();
+
+    // When
+    let mut reader = foreign_keys_as_arrow(
+        &conn,
+        "",
+        "",
+        "",
+        "",
+        "",
+        table_name,
+        &OdbcReaderBuilder::new(),
+    )
+    .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    assert_eq!(Arc::new(foreign_keys_schema()), batch.schema());
+    let pk_tables = batch
+        .column_by_name("PKTABLE_NAME")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(referenced_table, pk_tables.value(0));
+    let fk_columns = batch
+        .column_by_name("FKCOLUMN_NAME")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!("a", fk_columns.value(0));
+}
+
+#[test]
+fn fetch_primary_keys_as_arrow() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+
+    // Given a table with a primary key spanning two columns
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    conn.execute(&format!("DROP TABLE IF EXISTS {table_name}"), ())
+        .unwrap();
+    conn.execute(
+        &format!("CREATE TABLE {table_name} (a INTEGER, b INTEGER, PRIMARY KEY (b, a))"),
+        (),
+    )
+    .unwrap();
+
+    // When
+    let mut reader =
+        primary_keys_as_arrow(&conn, "", "", table_name, &OdbcReaderBuilder::new()).unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    assert_eq!(Arc::new(primary_keys_schema()), batch.schema());
+    let columns = batch
+        .column_by_name("COLUMN_NAME")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let key_seqs = batch
+        .column_by_name("KEY_SEQ")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int16Array>()
+        .unwrap();
+    let mut keys: Vec<_> = columns
+        .iter()
+        .zip(key_seqs.iter())
+        .map(|(column, key_seq)| (key_seq.unwrap(), column.unwrap()))
+        .collect();
+    keys.sort();
+    assert_eq!(vec![(1, "b"), (2, "a")], keys);
+}
+
+#[test]
+fn fetch_statistics_as_arrow() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let index_name = format!("{table_name}_a");
+
+    // Given a table with a unique index on column `a`
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["INTEGER"]).unwrap();
+    conn.execute(
+        &format!("CREATE UNIQUE INDEX {index_name} ON {table_name} (a)"),
+        (),
+    )
+    .unwrap();
+
+    // When
+    let mut reader = statistics_as_arrow(
+        &conn,
+        "",
+        "",
+        table_name,
+        true,
+        false,
+        &OdbcReaderBuilder::new(),
+    )
+    .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    assert_eq!(Arc::new(statistics_schema()), batch.schema());
+    let index_names = batch
+        .column_by_name("INDEX_NAME")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let position = index_names
+        .iter()
+        .position(|name| name == Some(index_name.as_str()))
+        .unwrap();
+    let columns = batch
+        .column_by_name("COLUMN_NAME")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!("a", columns.value(position));
+}
+
 /// Allocating octet length bytes is not enough if the column on the database is encoded in UTF-16
 /// since all codepoints in range from U+0800 to U+FFFF take three bytes in UTF-8 but only two bytes
 /// in UTF-16. We test this with the 'Trade Mark Sign' (`™`) (U+2122).
@@ -1124,6 +1489,112 @@ fn applies_memory_size_limit() {
     assert!(reader.max_rows_per_batch() < 65535)
 }
 
+#[test]
+fn applies_total_row_limit() {
+    // Given a cursor over five rows
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3),(4),(5)");
+
+    // When reading with a total limit of three rows and two rows per batch
+    let reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(2)
+        .with_max_total_rows(3)
+        .build(cursor)
+        .unwrap();
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+    // Then the second batch is sliced to contain only one row and no more batches are emitted
+    assert_eq!(2, batches.len());
+    assert_eq!(2, batches[0].num_rows());
+    assert_eq!(1, batches[1].num_rows());
+    let array_vals = batches[1]
+        .column(0)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!([3], *array_vals.values());
+}
+
+#[test]
+fn total_row_limit_limits_buffer_size() {
+    // Given a cursor
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3)");
+
+    // When setting a total row limit smaller than the rows per batch
+    let reader = OdbcReaderBuilder::new()
+        .with_max_total_rows(2)
+        .build(cursor)
+        .unwrap();
+
+    // Then the transit buffer does not hold more rows than needed
+    assert_eq!(2, reader.max_rows_per_batch())
+}
+
+#[test]
+fn applies_total_row_limit_concurrently() {
+    // Given a cursor over five rows
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3),(4),(5)");
+
+    // When reading concurrently with a total limit of three rows
+    let reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(2)
+        .with_max_total_rows(3)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+    let num_rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+
+    // Then
+    assert_eq!(3, num_rows);
+}
+
+#[test]
+fn report_progress_and_stats() {
+    // Given a cursor over three rows
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3)");
+
+    // When reading it in batches of two rows and recording progress
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let progress_in_callback = progress.clone();
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(2)
+        .with_progress(move |stats| progress_in_callback.lock().unwrap().push(stats.num_rows))
+        .build(cursor)
+        .unwrap();
+    for batch in &mut reader {
+        batch.unwrap();
+    }
+
+    // Then
+    assert_eq!(vec![2, 1], *progress.lock().unwrap());
+    let stats = reader.stats();
+    assert_eq!(3, stats.num_rows);
+    assert_eq!(2, stats.num_batches);
+    assert_eq!(1, stats.conversion_durations.len());
+    // Three 32 Bit integers
+    assert_eq!(12, stats.transit_bytes);
+}
+
+#[test]
+fn transit_bytes_account_for_length_of_text() {
+    // Given a cursor over text values, much shorter than the maximum length of the column
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "VARCHAR(100)", "('a'),('bcd'),(NULL)");
+
+    // When reading it
+    let mut reader = OdbcReaderBuilder::new().build(cursor).unwrap();
+    for batch in &mut reader {
+        batch.unwrap();
+    }
+
+    // Then only the bytes of the values are accounted for, rather than the size of the buffer
+    assert_eq!(4, reader.stats().transit_bytes);
+}
+
 #[test]
 fn memory_size_limit_can_not_hold_a_single_row() {
     // Given a cursor over a datascheme with a small per row memory footprint
@@ -1175,15 +1646,174 @@ fn insert_does_not_support_list_type() {
 }
 
 #[test]
-fn insert_text() {
-    // Given a table and a record batch reader returning a batch with a text column.
+#[cfg(feature = "json")]
+fn insert_list_as_json() {
+    // Given a table and a record batch with a list column
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
     setup_empty_table(&conn, table_name, &["VARCHAR(4096)"]).unwrap();
-    let array = StringArray::from(vec![Some("Hello"), None, Some("World")]);
-    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+    let array = arrow::array::ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), Some(2), None]),
+        None,
+        Some(vec![]),
+    ]);
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        array.data_type().clone(),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+
+    // When rendering nested types as JSON
+    let insert = format!("INSERT INTO {table_name} (a) VALUES (?)");
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.nested_as_json = true;
+    let mut writer = OdbcWriter::with_options(5, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.flush().unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "[1,2,null]\nNULL\n[]";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn insert_struct_as_json_for_selected_column() {
+    // Given a table and a record batch with a struct column
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["VARCHAR(4096)"]).unwrap();
+    let x: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None]));
+    let y: ArrayRef = Arc::new(StringArray::from(vec![Some("a\"b"), Some("c")]));
+    let array = StructArray::try_from(vec![("x", x), ("y", y)]).unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        array.data_type().clone(),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+
+    // When rendering only column `a` as JSON
+    let insert = format!("INSERT INTO {table_name} (a) VALUES (?)");
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.json_columns = vec!["a".to_owned()];
+    let mut writer = OdbcWriter::with_options(5, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.flush().unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "{\"x\":1,\"y\":\"a\\\"b\"}\n{\"x\":null,\"y\":\"c\"}";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_flattened_struct() {
+    // Given a table with one column for each child and a record batch with a struct column
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    conn.execute(&format!("DROP TABLE IF EXISTS {table_name}"), ())
+        .unwrap();
+    let create_table = format!(
+        "CREATE TABLE {table_name} (id int IDENTITY(1,1), address_street VARCHAR(50), \
+        address_zip INTEGER)"
+    );
+    conn.execute(&create_table, ()).unwrap();
+    let street: ArrayRef = Arc::new(StringArray::from(vec![Some("Main St"), Some("ignored")]));
+    let zip: ArrayRef = Arc::new(Int32Array::from(vec![12345, 0]));
+    let array = StructArray::new(
+        vec![
+            Field::new("street", DataType::Utf8, false),
+            Field::new("zip", DataType::Int32, false),
+        ]
+        .into(),
+        vec![street, zip],
+        Some(vec![true, false].into()),
+    );
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "address",
+        array.data_type().clone(),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+
+    // When flattening the struct, with the null parent nulling its children
+    let insert = insert_statement_from_schema(&flatten_schema(&schema, "_"), table_name);
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.flatten_structs = Some("_".to_owned());
+    let mut writer = OdbcWriter::with_options(5, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.flush().unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["address_street", "address_zip"]);
+    let expected = "Main St,12345\nNULL,NULL";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn create_table_from_schema_and_insert() {
+    // Given a schema and a record batch
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b b", DataType::Utf8, true),
+        Field::new("c", DataType::Decimal128(5, 2), true),
+    ]));
+    let a = Int32Array::from(vec![1, 2]);
+    let b = StringArray::from(vec![Some("Hello"), None]);
+    let c = Decimal128Array::from(vec![Some(12345), None])
+        .with_precision_and_scale(5, 2)
+        .unwrap();
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(a), Arc::new(b), Arc::new(c)]).unwrap();
+    let mut reader = StubBatchReader::new(schema.clone(), vec![batch]);
+
+    // When creating the table from the schema and inserting into it
+    let create_table =
+        create_table_statement_from_schema(&schema, table_name, SqlDialect::MsSql).unwrap();
+    assert_eq!(
+        format!(
+            "CREATE TABLE {table_name} (a INT NOT NULL, \"b b\" NVARCHAR(MAX) NULL, \
+            c DECIMAL(5,2) NULL)"
+        ),
+        create_table
+    );
+    conn.execute(&format!("DROP TABLE IF EXISTS {table_name}"), ())
+        .unwrap();
+    conn.execute(&create_table, ()).unwrap();
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a", "\"b b\"", "c"]);
+    let expected = "1,Hello,123.45\n2,NULL,NULL";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_text() {
+    // Given a table and a record batch reader returning a batch with a text column.
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["VARCHAR(4096)"]).unwrap();
+    let array = StringArray::from(vec![Some("Hello"), None, Some("World")]);
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
     let mut reader = StubBatchReader::new(schema, vec![batch]);
 
@@ -1400,6 +2030,89 @@ fn insert_non_nullable_unsigned_int8() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn insert_unsigned_integers() {
+    // Given a table and a record batch with unsigned integers of 16, 32 and 64 Bit
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["INTEGER", "BIGINT", "BIGINT"]).unwrap();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::UInt16, true),
+        Field::new("b", DataType::UInt32, false),
+        Field::new("c", DataType::UInt64, true),
+    ]));
+    let a = UInt16Array::from(vec![Some(u16::MAX), None]);
+    let b = UInt32Array::from(vec![u32::MAX, 0]);
+    let c = UInt64Array::from(vec![Some(i64::MAX as u64), None]);
+    let batch =
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(a), Arc::new(b), Arc::new(c)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+
+    // When
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a", "b", "c"]);
+    let expected = "65535,4294967295,9223372036854775807\nNULL,0,NULL";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_unsigned_64bit_integer_out_of_range() {
+    // Given a table and a record batch with an unsigned integer larger than `i64::MAX`
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["BIGINT"]).unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::UInt64, false)]));
+    let array = UInt64Array::from(vec![u64::MAX]);
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+
+    // When
+    let result = insert_into_table(&conn, &mut reader, table_name, 5);
+
+    // Then
+    assert!(matches!(
+        result,
+        Err(WriterError::ValueOutOfRange { value, .. }) if value == u64::MAX.to_string()
+    ));
+}
+
+#[test]
+fn insert_unsigned_64bit_integer_as_text() {
+    // Given a table and two record batches, with an unsigned integer larger than `i64::MAX` in the
+    // first and a short one in the same row of the second
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["DECIMAL(20,0)"]).unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::UInt64, true)]));
+    let array = UInt64Array::from(vec![Some(u64::MAX), None, Some(42)]);
+    let first = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let array = UInt64Array::from(vec![Some(7)]);
+    let second = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+
+    // When binding the integers as text, reusing the buffer for the second batch
+    let insert = format!("INSERT INTO {table_name} (a) VALUES (?)");
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.uint64_as_text = true;
+    let mut writer = OdbcWriter::with_options(3, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&first).unwrap();
+    writer.write_batch(&second).unwrap();
+    writer.flush().unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "18446744073709551615\nNULL\n42\n7";
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn insert_nullable_f32() {
     // Given a table and a record batch reader returning a batch with a text column.
@@ -1812,52 +2525,51 @@ fn insert_fixed_binary() {
 }
 
 #[test]
-fn insert_decimal_128() {
-    // Given a table and a record batch reader returning a batch with a text column.
+fn insert_large_binary() {
+    // Given a table and a record batch reader returning a batch with a large binary column.
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
-    setup_empty_table(&conn, table_name, &["NUMERIC(5,3)"]).unwrap();
-    let array: Decimal128Array = [Some(12345), None, Some(67891), Some(1), Some(1000)]
-        .into_iter()
-        .collect();
-    let array = array.with_precision_and_scale(5, 3).unwrap();
+    setup_empty_table(&conn, table_name, &["VARBINARY(4096)"]).unwrap();
+    let array = LargeBinaryArray::from(vec![
+        Some([1, 2].as_slice()),
+        None,
+        Some([3, 4, 5, 6, 7].as_slice()),
+    ]);
     let schema = Arc::new(Schema::new(vec![Field::new(
         "a",
-        DataType::Decimal128(5, 3),
+        DataType::LargeBinary,
         true,
     )]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
     let mut reader = StubBatchReader::new(schema, vec![batch]);
 
     // When
-    insert_into_table(&conn, &mut reader, table_name, 2).unwrap();
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
 
     // Then
     let actual = table_to_string(&conn, table_name, &["a"]);
-    let expected = "12.345\nNULL\n67.891\n.001\n1.000";
+    let expected = "0102\nNULL\n0304050607";
     assert_eq!(expected, actual);
 }
 
 #[test]
-fn insert_decimal_256() {
-    // Given a table and a record batch reader returning a batch with a text column.
+fn insert_fixed_size_binary() {
+    // Given a table and a record batch reader returning a batch with a fixed size binary column.
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
-    setup_empty_table(&conn, table_name, &["NUMERIC(5,3)"]).unwrap();
-    let mut builder = Decimal256Builder::new();
-    let mut bytes = [0u8; 32];
-    type I256 = <Decimal256Type as ArrowPrimitiveType>::Native;
-    bytes[0..4].copy_from_slice(12345i32.to_le_bytes().as_slice());
-    builder.append_value(I256::from_le_bytes(bytes));
-    builder.append_null();
-    let array = builder.finish().with_precision_and_scale(5, 3).unwrap();
+    setup_empty_table(&conn, table_name, &["BINARY(3)"]).unwrap();
+    let array = FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+        vec![Some([1, 2, 3].as_slice()), None, Some([4, 5, 6].as_slice())].into_iter(),
+        3,
+    )
+    .unwrap();
     let schema = Arc::new(Schema::new(vec![Field::new(
         "a",
-        DataType::Decimal256(5, 3),
+        DataType::FixedSizeBinary(3),
         true,
     )]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
@@ -1868,25 +2580,25 @@ fn insert_decimal_256() {
 
     // Then
     let actual = table_to_string(&conn, table_name, &["a"]);
-    let expected = "12.345\nNULL";
+    let expected = "010203\nNULL\n040506";
     assert_eq!(expected, actual);
 }
 
 #[test]
-fn insert_decimal_128_with_negative_scale() {
+fn insert_decimal_128() {
     // Given a table and a record batch reader returning a batch with a text column.
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
-    setup_empty_table(&conn, table_name, &["NUMERIC(5,0)"]).unwrap();
-    let array: Decimal128Array = [Some(123), None, Some(456), Some(1), Some(10)]
+    setup_empty_table(&conn, table_name, &["NUMERIC(5,3)"]).unwrap();
+    let array: Decimal128Array = [Some(12345), None, Some(67891), Some(1), Some(1000)]
         .into_iter()
         .collect();
-    let array = array.with_precision_and_scale(3, -2).unwrap();
+    let array = array.with_precision_and_scale(5, 3).unwrap();
     let schema = Arc::new(Schema::new(vec![Field::new(
         "a",
-        DataType::Decimal128(3, -2),
+        DataType::Decimal128(5, 3),
         true,
     )]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
@@ -1897,138 +2609,417 @@ fn insert_decimal_128_with_negative_scale() {
 
     // Then
     let actual = table_to_string(&conn, table_name, &["a"]);
-    let expected = "12300\nNULL\n45600\n100\n1000";
+    let expected = "12.345\nNULL\n67.891\n.001\n1.000";
     assert_eq!(expected, actual);
 }
 
 #[test]
-fn insert_decimal_256_with_negative_scale() {
-    // Given a table and a record batch reader returning a batch with a text column.
+fn insert_decimal_128_as_numeric_struct() {
+    // Given a table and a record batch with a decimal column, including negative values
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
-    setup_empty_table(&conn, table_name, &["NUMERIC(5,0)"]).unwrap();
-    let mut builder = Decimal256Builder::new();
-    let mut bytes = [0u8; 32];
-    type I256 = <Decimal256Type as ArrowPrimitiveType>::Native;
-    bytes[0..4].copy_from_slice(123i32.to_le_bytes().as_slice());
-    builder.append_value(I256::from_le_bytes(bytes));
-    builder.append_null();
-    let array = builder.finish().with_precision_and_scale(3, -2).unwrap();
+    setup_empty_table(&conn, table_name, &["NUMERIC(5,3)"]).unwrap();
+    let array: Decimal128Array = [Some(12345), None, Some(-67891), Some(1)]
+        .into_iter()
+        .collect();
+    let array = array.with_precision_and_scale(5, 3).unwrap();
     let schema = Arc::new(Schema::new(vec![Field::new(
         "a",
-        DataType::Decimal256(3, -2),
+        DataType::Decimal128(5, 3),
         true,
     )]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
-    let mut reader = StubBatchReader::new(schema, vec![batch]);
 
-    // When
-    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+    // When binding the decimals as numeric structs
+    let insert = format!("INSERT INTO {table_name} (a) VALUES (?)");
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.decimals_as_numeric_struct = true;
+    let mut writer = OdbcWriter::with_options(2, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.flush().unwrap();
 
     // Then
     let actual = table_to_string(&conn, table_name, &["a"]);
-    let expected = "12300\nNULL";
+    let expected = "12.345\nNULL\n-67.891\n.001";
     assert_eq!(expected, actual);
 }
 
 #[test]
-fn insert_taking_ownership_of_connection() {
-    // Given a table and a record batch reader returning a batch with a text column.
+fn insert_duration_as_big_int() {
+    // Given a table and a record batch reader returning a batch with a duration column
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
-    setup_empty_table(&conn, table_name, &["VARCHAR(4096)"]).unwrap();
-    let array = StringArray::from(vec![Some("Hello"), None, Some("World")]);
-    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+    setup_empty_table(&conn, table_name, &["BIGINT"]).unwrap();
+    let array = DurationMillisecondArray::from(vec![Some(1500), None, Some(-2)]);
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Duration(TimeUnit::Millisecond),
+        true,
+    )]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
-    let reader = StubBatchReader::new(schema.clone(), vec![batch]);
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
 
     // When
-    let mut inserter = {
-        let conn = ENV
-            .connect_with_connection_string(MSSQL, Default::default())
-            .unwrap();
-        let row_capacity = 50;
-        OdbcWriter::from_connection(conn, &schema, table_name, row_capacity).unwrap()
-    };
-    inserter.write_all(reader).unwrap();
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
 
     // Then
     let actual = table_to_string(&conn, table_name, &["a"]);
-    let expected = "Hello\nNULL\nWorld";
+    let expected = "1500\nNULL\n-2";
     assert_eq!(expected, actual);
 }
 
 #[test]
-fn insert_large_text() {
-    // Given a table and a record batch reader returning a batch with a text column.
+fn insert_duration_as_big_int_in_seconds() {
+    // Given a table and a record batch with durations in milliseconds
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
-    setup_empty_table(&conn, table_name, &["VARCHAR(4096)"]).unwrap();
-    let array = LargeStringArray::from(vec![Some("Hello"), None, Some("World")]);
+    setup_empty_table(&conn, table_name, &["BIGINT"]).unwrap();
+    let array = DurationMillisecondArray::from(vec![Some(1500), None, Some(-2500)]);
     let schema = Arc::new(Schema::new(vec![Field::new(
         "a",
-        DataType::LargeUtf8,
+        DataType::Duration(TimeUnit::Millisecond),
         true,
     )]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
-    let mut reader = StubBatchReader::new(schema, vec![batch]);
 
-    // When
-    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+    // When writing the durations as count of seconds
+    let insert = format!("INSERT INTO {table_name} (a) VALUES (?)");
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.duration_format = DurationFormat::BigInt {
+        unit: Some(TimeUnit::Second),
+    };
+    let mut writer = OdbcWriter::with_options(5, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.flush().unwrap();
 
-    // Then
+    // Then the milliseconds are truncated towards zero
     let actual = table_to_string(&conn, table_name, &["a"]);
-    let expected = "Hello\nNULL\nWorld";
+    let expected = "1\nNULL\n-2";
     assert_eq!(expected, actual);
 }
 
 #[test]
-fn sanatize_column_names() {
-    // Given a table with a column name containing a space ...
+fn insert_duration_as_interval_text() {
+    // Given a table and a record batch with durations in milliseconds
     let table_name = function_name!().rsplit_once(':').unwrap().1;
     let conn = ENV
         .connect_with_connection_string(MSSQL, Default::default())
         .unwrap();
-    let drop_table = &format!("DROP TABLE IF EXISTS {table_name}");
-    let create_table = format!("CREATE TABLE {table_name} (id int IDENTITY(1,1),\"column name with spaces\" INTEGER);");
-    conn.execute(drop_table, ()).unwrap();
-    conn.execute(&create_table, ()).unwrap();
-    let array = Int32Array::from(vec![Some(42)]);
-
-    // When inserting from a reader which features a schema with an unescaped column name
+    setup_empty_table(&conn, table_name, &["VARCHAR(50)"]).unwrap();
+    let array = DurationMillisecondArray::from(vec![Some(93_784_500), None, Some(-1500)]);
     let schema = Arc::new(Schema::new(vec![Field::new(
-        "column name with spaces",
-        DataType::Int32,
+        "a",
+        DataType::Duration(TimeUnit::Millisecond),
         true,
     )]));
     let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
-    let mut reader = StubBatchReader::new(schema, vec![batch]);
-    let result = insert_into_table(&conn, &mut reader, table_name, 1);
 
-    // Then the insert does not throw an error, but inserts correctly using the sanatized column
-    // name.
-    assert!(result.is_ok());
-    let actual = table_to_string(&conn, table_name, &["\"column name with spaces\""]);
-    let expected = "42";
+    // When writing the durations as SQL interval literals
+    let insert = format!("INSERT INTO {table_name} (a) VALUES (?)");
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.duration_format = DurationFormat::IntervalText;
+    let mut writer = OdbcWriter::with_options(5, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.flush().unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "1 02:03:04.500\nNULL\n-0 00:00:01.500";
     assert_eq!(expected, actual);
 }
 
-/// Fill a record batch with non nullable Integer 32 Bit directly from the datasource
 #[test]
-fn fetch_integer_concurrently() {
+fn insert_interval_as_iso_8601_text() {
+    // Given a table and a record batch reader returning a batch with an interval column
     let table_name = function_name!().rsplit_once(':').unwrap().1;
-    let cursor = cursor_over(table_name, "INTEGER", "(1),(NULL),(3)");
-    // Now that we have a cursor, we want to iterate over its rows and fill an arrow batch with it.
-    let mut reader = OdbcReaderBuilder::new()
-        .with_max_num_rows_per_batch(100)
-        .build(cursor)
-        .unwrap();
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["VARCHAR(60)"]).unwrap();
+    let nanos = (4 * 3600 + 5 * 60 + 6) * 1_000_000_000 + 700_000_000;
+    let array = IntervalMonthDayNanoArray::from(vec![
+        Some(IntervalMonthDayNanoType::make_value(14, 3, nanos)),
+        None,
+        Some(IntervalMonthDayNanoType::make_value(0, -1, 0)),
+        Some(IntervalMonthDayNanoType::make_value(0, 0, 0)),
+    ]);
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Interval(IntervalUnit::MonthDayNano),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+
+    // When
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "P1Y2M3DT4H5M6.7S\nNULL\nP-1D\nPT0S";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_and_fetch_interval_as_sql_interval() {
+    // Given a PostgreSQL table with interval columns and a record batch with intervals
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(POSTGRES, Default::default())
+        .unwrap();
+    conn.execute(&format!("DROP TABLE IF EXISTS {table_name}"), ())
+        .unwrap();
+    let create_table = format!(
+        "CREATE TABLE {table_name} (id SERIAL PRIMARY KEY, a INTERVAL YEAR TO MONTH, \
+        b INTERVAL DAY TO SECOND)"
+    );
+    conn.execute(&create_table, ()).unwrap();
+    let nanos = (4 * 3600 + 5 * 60 + 6) * 1_000_000_000 + 700_000_000;
+    let year_month = IntervalYearMonthArray::from(vec![Some(14), None, Some(-1), Some(0)]);
+    let day_to_second = IntervalMonthDayNanoArray::from(vec![
+        Some(IntervalMonthDayNanoType::make_value(0, 3, nanos)),
+        None,
+        Some(IntervalMonthDayNanoType::make_value(0, -1, -nanos)),
+        Some(IntervalMonthDayNanoType::make_value(0, 0, 0)),
+    ]);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Interval(IntervalUnit::YearMonth), true),
+        Field::new("b", DataType::Interval(IntervalUnit::MonthDayNano), true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(year_month.clone()),
+            Arc::new(day_to_second.clone()),
+        ],
+    )
+    .unwrap();
+
+    // When writing the intervals as interval structs and fetching them again
+    let insert = format!("INSERT INTO {table_name} (a, b) VALUES (?, ?)");
+    let prepared = conn.prepare(&insert).unwrap();
+    let mut options = WriterOptions::default();
+    options.interval_format = IntervalFormat::SqlInterval;
+    let mut writer = OdbcWriter::with_options(5, schema.as_ref(), prepared, &options).unwrap();
+    writer.write_batch(&batch).unwrap();
+    writer.flush().unwrap();
+    let query = format!("SELECT a, b FROM {table_name} ORDER BY id");
+    let cursor = conn.execute(&query, ()).unwrap().unwrap();
+    let mut reader = OdbcReaderBuilder::new()
+        .with_schema(schema)
+        .build(cursor)
+        .unwrap();
+    let fetched = reader.next().unwrap().unwrap();
+
+    // Then
+    assert_eq!(
+        &year_month,
+        fetched.column(0).as_primitive::<IntervalYearMonthType>()
+    );
+    assert_eq!(
+        &day_to_second,
+        fetched.column(1).as_primitive::<IntervalMonthDayNanoType>()
+    );
+}
+
+#[test]
+fn insert_decimal_256() {
+    // Given a table and a record batch reader returning a batch with a text column.
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["NUMERIC(5,3)"]).unwrap();
+    let mut builder = Decimal256Builder::new();
+    let mut bytes = [0u8; 32];
+    type I256 = <Decimal256Type as ArrowPrimitiveType>::Native;
+    bytes[0..4].copy_from_slice(12345i32.to_le_bytes().as_slice());
+    builder.append_value(I256::from_le_bytes(bytes));
+    builder.append_null();
+    let array = builder.finish().with_precision_and_scale(5, 3).unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Decimal256(5, 3),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+
+    // When
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "12.345\nNULL";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_decimal_128_with_negative_scale() {
+    // Given a table and a record batch reader returning a batch with a text column.
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["NUMERIC(5,0)"]).unwrap();
+    let array: Decimal128Array = [Some(123), None, Some(456), Some(1), Some(10)]
+        .into_iter()
+        .collect();
+    let array = array.with_precision_and_scale(3, -2).unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Decimal128(3, -2),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+
+    // When
+    insert_into_table(&conn, &mut reader, table_name, 2).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "12300\nNULL\n45600\n100\n1000";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_decimal_256_with_negative_scale() {
+    // Given a table and a record batch reader returning a batch with a text column.
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["NUMERIC(5,0)"]).unwrap();
+    let mut builder = Decimal256Builder::new();
+    let mut bytes = [0u8; 32];
+    type I256 = <Decimal256Type as ArrowPrimitiveType>::Native;
+    bytes[0..4].copy_from_slice(123i32.to_le_bytes().as_slice());
+    builder.append_value(I256::from_le_bytes(bytes));
+    builder.append_null();
+    let array = builder.finish().with_precision_and_scale(3, -2).unwrap();
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Decimal256(3, -2),
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+
+    // When
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "12300\nNULL";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_taking_ownership_of_connection() {
+    // Given a table and a record batch reader returning a batch with a text column.
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["VARCHAR(4096)"]).unwrap();
+    let array = StringArray::from(vec![Some("Hello"), None, Some("World")]);
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let reader = StubBatchReader::new(schema.clone(), vec![batch]);
+
+    // When
+    let mut inserter = {
+        let conn = ENV
+            .connect_with_connection_string(MSSQL, Default::default())
+            .unwrap();
+        let row_capacity = 50;
+        OdbcWriter::from_connection(conn, &schema, table_name, row_capacity).unwrap()
+    };
+    inserter.write_all(reader).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "Hello\nNULL\nWorld";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn insert_large_text() {
+    // Given a table and a record batch reader returning a batch with a text column.
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["VARCHAR(4096)"]).unwrap();
+    let array = LargeStringArray::from(vec![Some("Hello"), None, Some("World")]);
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::LargeUtf8,
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+
+    // When
+    insert_into_table(&conn, &mut reader, table_name, 5).unwrap();
+
+    // Then
+    let actual = table_to_string(&conn, table_name, &["a"]);
+    let expected = "Hello\nNULL\nWorld";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn sanatize_column_names() {
+    // Given a table with a column name containing a space ...
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    let drop_table = &format!("DROP TABLE IF EXISTS {table_name}");
+    let create_table = format!("CREATE TABLE {table_name} (id int IDENTITY(1,1),\"column name with spaces\" INTEGER);");
+    conn.execute(drop_table, ()).unwrap();
+    conn.execute(&create_table, ()).unwrap();
+    let array = Int32Array::from(vec![Some(42)]);
+
+    // When inserting from a reader which features a schema with an unescaped column name
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "column name with spaces",
+        DataType::Int32,
+        true,
+    )]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)]).unwrap();
+    let mut reader = StubBatchReader::new(schema, vec![batch]);
+    let result = insert_into_table(&conn, &mut reader, table_name, 1);
+
+    // Then the insert does not throw an error, but inserts correctly using the sanatized column
+    // name.
+    assert!(result.is_ok());
+    let actual = table_to_string(&conn, table_name, &["\"column name with spaces\""]);
+    let expected = "42";
+    assert_eq!(expected, actual);
+}
+
+/// Fill a record batch with non nullable Integer 32 Bit directly from the datasource
+#[test]
+fn fetch_integer_concurrently() {
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(NULL),(3)");
+    // Now that we have a cursor, we want to iterate over its rows and fill an arrow batch with it.
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(100)
+        .build(cursor)
+        .unwrap();
     // Batch for batch copy values from ODBC buffer into arrow batches
     let record_batch = reader.next().unwrap().unwrap();
 
@@ -2191,27 +3182,782 @@ fn promote_sequential_to_concurrent_cursor() {
 }
 
 #[test]
-fn concurrent_reader_is_send() {
-    // Given a conucurrent_reader
+fn cancel_read() {
+    // Given a reader
     let table_name = function_name!().rsplit_once(':').unwrap().1;
-    let cursor = cursor_over(table_name, "INTEGER", "(42)");
-    let mut concurrent_reader = OdbcReaderBuilder::new()
-        .with_max_num_rows_per_batch(100)
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3)");
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(1)
         .build(cursor)
-        .unwrap()
-        .into_concurrent()
         .unwrap();
+    reader.next().unwrap().unwrap();
 
-    // When send to another thread. compile time error otherwise, most important implicit assertion
-    // of this test.
-    let record_batch = thread::spawn(move || concurrent_reader.next().unwrap().unwrap())
+    // When cancelling it from another thread
+    let cancel_handle = reader.cancel_handle();
+    thread::spawn(move || cancel_handle.cancel().unwrap())
         .join()
         .unwrap();
 
-    // Then
-    let array_any = record_batch.column(0).clone();
-    let array_vals = array_any.as_any().downcast_ref::<Int32Array>().unwrap();
-    assert_eq!([42], *array_vals.values());
+    // Then the reader emits a `Cancelled` error and stops
+    let error = reader.next().unwrap().unwrap_err();
+    let ArrowError::ExternalError(source) = error else {
+        panic!("Expected external error")
+    };
+    assert!(source.downcast_ref::<Cancelled>().is_some());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn cancel_concurrent_read() {
+    // Given a concurrent reader
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3)");
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(1)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+
+    // When
+    reader.cancel_handle().cancel().unwrap();
+
+    // Then
+    let error = reader.next().unwrap().unwrap_err();
+    let ArrowError::ExternalError(source) = error else {
+        panic!("Expected external error")
+    };
+    assert!(source.downcast_ref::<Cancelled>().is_some());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn cancel_concurrent_read_after_fetch_error() {
+    // Given a concurrent reader over a query, which fails with a division by zero in its last row
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, ConnectionOptions::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["INTEGER"]).unwrap();
+    let sql = format!(
+        "INSERT INTO {table_name} (a) SELECT TOP 5000 1 FROM sys.all_objects AS x \
+        CROSS JOIN sys.all_objects AS y"
+    );
+    conn.execute(&sql, ()).unwrap();
+    let sql = format!("INSERT INTO {table_name} (a) VALUES (0)");
+    conn.execute(&sql, ()).unwrap();
+    let sql = format!("SELECT 1 / a AS a FROM {table_name} ORDER BY id");
+    let cursor = conn.into_cursor(&sql, ()).unwrap().unwrap();
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(100)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+    let error = loop {
+        match reader.next() {
+            Some(Ok(_)) => (),
+            Some(Err(error)) => break error,
+            None => panic!("Expected fetch to fail"),
+        }
+    };
+    assert!(matches!(error, ArrowError::ExternalError(_)));
+
+    // When cancelling after the fetch thread has freed the statement
+    let result = reader.cancel_handle().cancel();
+
+    // Then the freed statement is not touched, and the reader reports the cancellation
+    assert!(result.is_ok());
+    let ArrowError::ExternalError(source) = reader.next().unwrap().unwrap_err() else {
+        panic!("Expected external error")
+    };
+    assert!(source.downcast_ref::<Cancelled>().is_some());
+}
+
+#[test]
+fn concurrent_reader_is_send() {
+    // Given a conucurrent_reader
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(42)");
+    let mut concurrent_reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(100)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+
+    // When send to another thread. compile time error otherwise, most important implicit assertion
+    // of this test.
+    let record_batch = thread::spawn(move || concurrent_reader.next().unwrap().unwrap())
+        .join()
+        .unwrap();
+
+    // Then
+    let array_any = record_batch.column(0).clone();
+    let array_vals = array_any.as_any().downcast_ref::<Int32Array>().unwrap();
+    assert_eq!([42], *array_vals.values());
+}
+
+#[test]
+fn concurrent_read_with_prefetch_depth() {
+    // Given a cursor over five rows
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3),(4),(5)");
+
+    // When reading concurrently in batches of one row, allowing for three batches to be prefetched
+    let reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(1)
+        .with_prefetch_depth(3)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+    let values: Vec<i32> = reader
+        .flat_map(|batch| {
+            let batch = batch.unwrap();
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap()
+                .clone();
+            array.values().to_vec()
+        })
+        .collect();
+
+    // Then all rows are emitted in order
+    assert_eq!(vec![1, 2, 3, 4, 5], values);
+}
+
+#[test]
+fn memory_budget_for_all_transit_buffers() {
+    // Given a cursor over a single INTEGER column (4 bytes + 8 bytes indicator per row)
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1)");
+
+    // When sharing a budget of 48 bytes between a prefetch depth of 3, i.e. four transit buffers
+    let reader = OdbcReaderBuilder::new()
+        .with_prefetch_depth(3)
+        .with_max_bytes_in_transit(48)
+        .build(cursor)
+        .unwrap();
+
+    // Then each buffer holds a single row
+    assert_eq!(1, reader.max_rows_per_batch());
+}
+
+#[test]
+fn concurrent_read_with_background_conversion() {
+    // Given a cursor over five rows
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(
+        table_name,
+        "VARCHAR(10)",
+        "('one'),('two'),('three'),(NULL),('five')",
+    );
+
+    // When reading concurrently in batches of two rows, converting them in a background thread
+    let reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(2)
+        .with_prefetch_depth(2)
+        .with_background_conversion(true)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+    let values: Vec<Option<String>> = reader
+        .flat_map(|batch| {
+            let batch = batch.unwrap();
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap()
+                .clone();
+            array
+                .iter()
+                .map(|value| value.map(str::to_owned))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    // Then all rows are emitted in order
+    assert_eq!(
+        vec![
+            Some("one".to_owned()),
+            Some("two".to_owned()),
+            Some("three".to_owned()),
+            None,
+            Some("five".to_owned())
+        ],
+        values
+    );
+}
+
+#[test]
+fn drop_reader_with_background_conversion_before_consuming_it() {
+    // Given a reader converting batches in a background thread, which has emitted the first batch
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "INTEGER", "(1),(2),(3),(4),(5)");
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(1)
+        .with_background_conversion(true)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+    reader.next().unwrap().unwrap();
+    let cancel_handle = reader.cancel_handle();
+
+    // When dropping it, both the conversion and the fetch thread are stopped and joined
+    drop(reader);
+
+    // Then the statement has been freed, so cancelling has no effect
+    cancel_handle.cancel().unwrap();
+}
+
+#[test]
+fn read_partitions_merged() {
+    // Given a table with ten rows, with ids from 1 to 10
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, ConnectionOptions::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["INTEGER"]).unwrap();
+    let sql =
+        format!("INSERT INTO {table_name} (a) VALUES (1),(2),(3),(4),(5),(6),(7),(8),(9),(10)");
+    conn.execute(&sql, ()).unwrap();
+
+    // When reading it in three partitions over the id column
+    let partitions = PartitionSpec::Range {
+        column: "id".to_owned(),
+        min: 1,
+        max: 10,
+        num_partitions: 3,
+    };
+    let reader = PartitionedReader::new(
+        || ENV.connect_with_connection_string(MSSQL, ConnectionOptions::default()),
+        &format!("SELECT id, a FROM {table_name}"),
+        &partitions,
+        &OdbcReaderBuilder::new(),
+    )
+    .unwrap();
+    let mut values: Vec<i32> = reader
+        .into_merged()
+        .flat_map(|batch| {
+            let batch = batch.unwrap();
+            let array = batch
+                .column(1)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap()
+                .clone();
+            array.values().to_vec()
+        })
+        .collect();
+
+    // Then each row is emitted exactly once
+    values.sort();
+    assert_eq!((1..=10).collect::<Vec<_>>(), values);
+}
+
+#[test]
+fn partition_spec_without_partitions() {
+    // Given a partition spec without any predicate
+    let partitions = PartitionSpec::Predicates(Vec::new());
+
+    // When
+    let result = PartitionedReader::new(
+        || ENV.connect_with_connection_string(MSSQL, ConnectionOptions::default()),
+        "SELECT 1",
+        &partitions,
+        &OdbcReaderBuilder::new(),
+    );
+
+    // Then
+    assert!(matches!(result, Err(Error::NoPartitions)));
+}
+
+#[test]
+fn read_partitions_by_predicates() {
+    // Given a table with five rows
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, ConnectionOptions::default())
+        .unwrap();
+    setup_empty_table(&conn, table_name, &["INTEGER"]).unwrap();
+    let sql = format!("INSERT INTO {table_name} (a) VALUES (1),(2),(3),(4),(5)");
+    conn.execute(&sql, ()).unwrap();
+
+    // When reading it in two partitions given by explicit predicates
+    let partitions = PartitionSpec::Predicates(vec!["a < 3".to_owned(), "a >= 3".to_owned()]);
+    let reader = PartitionedReader::new(
+        || ENV.connect_with_connection_string(MSSQL, ConnectionOptions::default()),
+        &format!("SELECT a FROM {table_name}"),
+        &partitions,
+        &OdbcReaderBuilder::new(),
+    )
+    .unwrap();
+    let num_rows: Vec<usize> = reader
+        .into_readers()
+        .into_iter()
+        .map(|reader| reader.map(|batch| batch.unwrap().num_rows()).sum())
+        .collect();
+
+    // Then there is one reader for each partition
+    assert_eq!(vec![2, 3], num_rows);
+}
+
+#[test]
+fn conversion_error_reports_position() {
+    // Given a cursor with an out of range timestamp in its fourth row
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(
+        table_name,
+        "DATETIME2",
+        "('2000-01-01'),('2000-01-02'),(NULL),('2300-01-01 00:00:00.1234567')",
+    );
+
+    // When reading it in batches of two
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(2)
+        .build(cursor)
+        .unwrap();
+    reader.next().unwrap().unwrap();
+    let error = reader.next().unwrap().unwrap_err();
+
+    // Then the error tells us the column and the row of the offending value
+    let conversion_error = ConversionError::from_arrow_error(&error).unwrap();
+    assert_eq!(0, conversion_error.column_index);
+    assert_eq!("a", conversion_error.column_name);
+    assert_eq!(1, conversion_error.row_index);
+    assert_eq!(3, conversion_error.absolute_row_index);
+    assert!(matches!(
+        conversion_error.source,
+        MappingError::OutOfRangeTimestampNs { .. }
+    ));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn fetch_json_text_as_struct() {
+    // Given a cursor over a text column holding JSON objects
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(
+        table_name,
+        "VARCHAR(100)",
+        r#"('{"x": 1, "y": ["a", "b"]}'),(NULL),('{"y": [], "z": true}')"#,
+    );
+
+    // When reading it as a struct
+    let item = Arc::new(Field::new("item", DataType::Utf8, true));
+    let fields = vec![
+        Field::new("x", DataType::Int32, true),
+        Field::new("y", DataType::List(item), true),
+    ];
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Struct(fields.into()),
+        true,
+    )]));
+    let mut reader = OdbcReaderBuilder::new()
+        .with_schema(schema)
+        .with_struct_format("a", StructFormat::JsonText)
+        .build(cursor)
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    let array = batch.column(0).as_struct();
+    assert!(array.is_valid(0));
+    assert!(array.is_null(1));
+    assert!(array.is_valid(2));
+    let x = array.column(0).as_primitive::<Int32Type>();
+    assert_eq!(1, x.value(0));
+    assert!(x.is_null(2));
+    let y = array.column(1).as_list::<i32>();
+    let first = y.value(0);
+    let first = first.as_string::<i32>();
+    assert_eq!(vec![Some("a"), Some("b")], first.iter().collect::<Vec<_>>());
+    assert_eq!(0, y.value(2).len());
+}
+
+#[test]
+fn fetch_struct_from_consecutive_columns() {
+    // Given a cursor over a flat result set, there two columns hold the children of a struct
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    let query = "SELECT a, address_street, address_zip, b FROM \
+        (VALUES (1, 'Main St', 12345, 2), (3, NULL, NULL, 4)) AS t (a, address_street, address_zip, b) \
+        ORDER BY a";
+    let cursor = conn.into_cursor(query, ()).unwrap().unwrap();
+
+    // When reading the children as a nullable struct
+    let address = vec![
+        Field::new("street", DataType::Utf8, false),
+        Field::new("zip", DataType::Int32, false),
+    ];
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("address", DataType::Struct(address.into()), true),
+        Field::new("b", DataType::Int32, false),
+    ]));
+    let mut reader = OdbcReaderBuilder::new()
+        .with_schema(schema)
+        .with_struct_format("address", StructFormat::Columns)
+        .build(cursor)
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then the struct is null there all its children are, and subsequent fields are shifted
+    let address = batch.column(1).as_struct();
+    assert!(address.is_valid(0));
+    assert!(address.is_null(1));
+    let street = address.column(0).as_string::<i32>();
+    assert_eq!("Main St", street.value(0));
+    let zip = address.column(1).as_primitive::<Int32Type>();
+    assert_eq!(12345, zip.value(0));
+    let b = batch.column(2).as_primitive::<Int32Type>();
+    assert_eq!(&[2, 4], b.values());
+    // Children of a nullable struct must be nullable, too
+    let DataType::Struct(fields) = batch.schema().field(1).data_type().clone() else {
+        panic!("Expected struct")
+    };
+    assert!(fields.iter().all(|field| field.is_nullable()));
+}
+
+#[test]
+fn fetch_struct_from_name_prefixed_columns() {
+    // Given a cursor over columns named after the path of the children, in a different order
+    let conn = ENV
+        .connect_with_connection_string(MSSQL, Default::default())
+        .unwrap();
+    let query = "SELECT zip AS \"address.zip\", street AS \"address.street\" FROM \
+        (VALUES (12345, 'Main St')) AS t (zip, street)";
+    let cursor = conn.into_cursor(query, ()).unwrap().unwrap();
+
+    // When
+    let address = vec![
+        Field::new("street", DataType::Utf8, true),
+        Field::new("zip", DataType::Int32, true),
+    ];
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "address",
+        DataType::Struct(address.into()),
+        false,
+    )]));
+    let mut reader = OdbcReaderBuilder::new()
+        .with_schema(schema)
+        .with_struct_format("address", StructFormat::Columns)
+        .build(cursor)
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then children are matched by name
+    let address = batch.column(0).as_struct();
+    assert_eq!("Main St", address.column(0).as_string::<i32>().value(0));
+    assert_eq!(
+        12345,
+        address.column(1).as_primitive::<Int32Type>().value(0)
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn struct_without_explicit_format_is_ambiguous() {
+    // Given a cursor over a text column
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "VARCHAR(100)", r#"('{"x": 1}')"#);
+
+    // When reading it as a struct, which could also be parsed from JSON, without choosing a format
+    let fields = vec![Field::new("x", DataType::Int32, true)];
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::Struct(fields.into()),
+        true,
+    )]));
+    let result = OdbcReaderBuilder::new().with_schema(schema).build(cursor);
+
+    // Then
+    assert!(matches!(
+        result,
+        Err(Error::ColumnFailure {
+            source: ColumnFailure::AmbiguousStructFormat,
+            index: 0,
+            name: _
+        })
+    ))
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn invalid_json_text_is_reported_as_mapping_error() {
+    // Given a cursor whose second row holds a string where a number is expected
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "VARCHAR(100)", r#"('[1, 2]'),('[1, "two"]')"#);
+
+    // When reading it as list of integers
+    let item = Arc::new(Field::new("item", DataType::Int64, true));
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "a",
+        DataType::List(item),
+        true,
+    )]));
+    let mut reader = OdbcReaderBuilder::new()
+        .with_schema(schema)
+        .build(cursor)
+        .unwrap();
+    let error = reader.next().unwrap().unwrap_err();
+
+    // Then
+    let conversion_error = ConversionError::from_arrow_error(&error).unwrap();
+    assert_eq!(1, conversion_error.row_index);
+    assert!(matches!(
+        conversion_error.source,
+        MappingError::InvalidJson { .. }
+    ));
+}
+
+#[test]
+fn null_and_record_out_of_range_timestamp() {
+    // Given a cursor with an out of range timestamp in its second row
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(
+        table_name,
+        "DATETIME2",
+        "('2000-01-01'),('2300-01-01 00:00:00.1234567'),('2000-01-03')",
+    );
+
+    // When reading it, replacing values which can not be converted with NULL
+    let mut reader = OdbcReaderBuilder::new()
+        .with_on_conversion_error(OnConversionError::NullAndRecord)
+        .build(cursor)
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+    let rejects = reader.take_rejects();
+
+    // Then the offending value is NULL and recorded as a reject
+    let array = batch.column(0);
+    assert_eq!(3, array.len());
+    assert!(array.is_null(1));
+    assert_eq!(1, rejects.num_rows());
+    let row_numbers = rejects
+        .column_by_name("row_number")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<UInt64Array>()
+        .unwrap();
+    assert_eq!(1, row_numbers.value(0));
+    let column_names = rejects
+        .column_by_name("column_name")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!("a", column_names.value(0));
+    let raw_values = rejects
+        .column_by_name("raw_value")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!("2300-01-01 00:00:00.123456700", raw_values.value(0));
+    // Rejects are drained
+    assert_eq!(0, reader.take_rejects().num_rows());
+}
+
+#[test]
+fn reject_row_number_counts_rows_read_before_background_conversion() {
+    // Given a reader over an out of range timestamp in the third row, which already emitted the
+    // first row
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(
+        table_name,
+        "DATETIME2",
+        "('2000-01-01'),('2000-01-02'),('2300-01-01 00:00:00.1234567')",
+    );
+    let mut reader = OdbcReaderBuilder::new()
+        .with_max_num_rows_per_batch(1)
+        .with_on_conversion_error(OnConversionError::NullAndRecord)
+        .with_background_conversion(true)
+        .build(cursor)
+        .unwrap();
+    reader.next().unwrap().unwrap();
+
+    // When converting the remaining rows in a background thread
+    let mut reader = reader.into_concurrent().unwrap();
+    reader.next().unwrap().unwrap();
+    reader.next().unwrap().unwrap();
+    let rejects = reader.take_rejects();
+
+    // Then the row number counts from the start of the cursor
+    let row_numbers = rejects
+        .column_by_name("row_number")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<UInt64Array>()
+        .unwrap();
+    assert_eq!(&[2], row_numbers.values());
+}
+
+/// With the `rayon` feature columns are converted in parallel. Errors and rejects must still be
+/// reported in column order.
+#[test]
+#[cfg(feature = "rayon")]
+fn convert_columns_in_parallel() {
+    // Given a result set with out of range timestamps in two columns of the same row
+    let query = "SELECT a, b, c FROM (VALUES \
+        (1, CAST('2300-01-01' AS DATETIME2), CAST('2300-01-02' AS DATETIME2)), \
+        (2, CAST('2000-01-01' AS DATETIME2), CAST('2000-01-02' AS DATETIME2))) AS t (a, b, c) \
+        ORDER BY a";
+    let cursor = || {
+        ENV.connect_with_connection_string(MSSQL, Default::default())
+            .unwrap()
+            .into_cursor(query, ())
+            .unwrap()
+            .unwrap()
+    };
+
+    // When converting the columns in parallel, failing on the first error
+    let mut reader = OdbcReaderBuilder::new().build(cursor()).unwrap();
+    let error = reader.next().unwrap().unwrap_err();
+
+    // Then the error of the first failing column is reported
+    let conversion_error = ConversionError::from_arrow_error(&error).unwrap();
+    assert_eq!(1, conversion_error.column_index);
+
+    // When recording rejects instead
+    let mut reader = OdbcReaderBuilder::new()
+        .with_on_conversion_error(OnConversionError::NullAndRecord)
+        .build(cursor())
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+    let rejects = reader.take_rejects();
+
+    // Then both values are NULL and recorded in column order
+    assert!(batch.column(1).is_null(0));
+    assert!(batch.column(2).is_null(0));
+    let column_indices = rejects
+        .column_by_name("column_index")
+        .unwrap()
+        .as_primitive::<arrow::datatypes::UInt32Type>();
+    assert_eq!(&[1, 2], column_indices.values());
+}
+
+#[test]
+fn drop_and_record_out_of_range_timestamp() {
+    // Given a cursor with an out of range timestamp in its second row
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(
+        table_name,
+        "DATETIME2 NOT NULL",
+        "('2000-01-01'),('2300-01-01 00:00:00.1234567'),('2000-01-03')",
+    );
+
+    // When reading it concurrently, dropping rows with values which can not be converted
+    let mut reader = OdbcReaderBuilder::new()
+        .with_on_conversion_error(OnConversionError::DropAndRecord)
+        .build(cursor)
+        .unwrap()
+        .into_concurrent()
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then the row is missing in the batch and recorded as a reject
+    assert_eq!(2, batch.num_rows());
+    assert_eq!(1, reader.take_rejects().num_rows());
+}
+
+/// MSSQL does not allow us to store dates which do not exist, so we can only assert that valid
+/// dates are not affected by the policy.
+#[test]
+fn invalid_date_time_policy_keeps_valid_dates() {
+    // Given a cursor over dates, including NULL
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "DATE", "('2021-04-09'),(NULL)");
+
+    // When reading it, with a sentinel for invalid dates
+    let sentinel = NaiveDate::from_ymd_opt(1970, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let mut reader = OdbcReaderBuilder::new()
+        .with_on_invalid_date_time(OnInvalidDateTime::Sentinel(sentinel))
+        .build(cursor)
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    let array_vals = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<Date32Array>()
+        .unwrap();
+    assert_eq!(
+        Some(NaiveDate::from_ymd_opt(2021, 4, 9).unwrap()),
+        array_vals.value_as_date(0)
+    );
+    assert!(array_vals.is_null(1));
+}
+
+/// Open ended validity intervals are often marked with `9999-12-31`, which is out of range for
+/// nanoseconds precision.
+#[test]
+fn saturate_out_of_range_timestamp() {
+    // Given a cursor over a timestamp far in the future
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "DATETIME2", "('9999-12-31 00:00:00'),(NULL)");
+
+    // When reading it, saturating out of range timestamps
+    let mut reader = OdbcReaderBuilder::new()
+        .with_out_of_range_timestamp(OutOfRangeTimestamp::Saturate)
+        .build(cursor)
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then
+    let array_vals = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<TimestampNanosecondArray>()
+        .unwrap();
+    assert_eq!(i64::MAX, array_vals.value(0));
+    assert!(array_vals.is_null(1));
+}
+
+#[test]
+fn infer_microseconds_for_out_of_range_timestamp() {
+    // Given a cursor over a timestamp far in the future
+    let table_name = function_name!().rsplit_once(':').unwrap().1;
+    let cursor = cursor_over(table_name, "DATETIME2", "('9999-12-31 23:59:59.1234567')");
+
+    // When reading it, inferring microseconds precision
+    let mut reader = OdbcReaderBuilder::new()
+        .with_out_of_range_timestamp(OutOfRangeTimestamp::Microseconds)
+        .build(cursor)
+        .unwrap();
+    let batch = reader.next().unwrap().unwrap();
+
+    // Then the value is truncated to microseconds
+    assert_eq!(
+        &DataType::Timestamp(TimeUnit::Microsecond, None),
+        batch.schema().field(0).data_type()
+    );
+    let array_vals = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<TimestampMicrosecondArray>()
+        .unwrap();
+    assert_eq!(
+        Some(
+            NaiveDate::from_ymd_opt(9999, 12, 31)
+                .unwrap()
+                .and_hms_micro_opt(23, 59, 59, 123456)
+                .unwrap()
+        ),
+        array_vals.value_as_datetime(0)
+    );
 }
 
 /// Creates the table and assures it is empty. Columns are named a,b,c, etc.